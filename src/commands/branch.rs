@@ -0,0 +1,303 @@
+use std::path::Path;
+use clap::Subcommand;
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use super::{plural, require_terminal, run_git};
+use crate::git;
+use crate::github;
+use crate::project::{self, BranchConfig, Finish};
+
+#[derive(Subcommand)]
+pub enum BranchAction {
+    /// Create a branch off the updated default branch and push it with upstream
+    New {
+        /// Branch name; the default prefix from CONFIG.toml is added if it has none
+        name: String,
+        /// Branch off this branch instead of the default branch
+        #[arg(long)]
+        from: Option<String>,
+        /// Create the branch locally only
+        #[arg(long)]
+        no_push: bool,
+    },
+    /// Switch branches with a fuzzy picker
+    Switch {
+        /// Part of the branch name; switches directly if only one branch matches
+        query: Option<String>,
+    },
+    /// Open a pull request for the current branch, or merge it back and delete it
+    #[command(group(clap::ArgGroup::new("mode").args(["pr", "merge"])))]
+    Finish {
+        /// Open a pull request (default unless CONFIG.toml says otherwise)
+        #[arg(long)]
+        pr: bool,
+        /// Merge into the default branch, push, and delete the branch
+        #[arg(long)]
+        merge: bool,
+    },
+}
+
+pub fn run(action: &BranchAction) {
+    let repo = Path::new(".");
+    if git::output(repo, &["rev-parse", "--git-dir"]).is_none() {
+        eprintln!("Not in a Git repository.");
+        std::process::exit(1);
+    }
+    let config = project::load(repo).branch;
+
+    let result = match action {
+        BranchAction::New { name, from, no_push } => new(repo, &config, name, from.as_deref(), *no_push),
+        BranchAction::Switch { query } => switch(repo, query.as_deref()),
+        BranchAction::Finish { pr, merge } => {
+            let mode = if *merge {
+                Finish::Merge
+            } else if *pr {
+                Finish::Pr
+            } else {
+                config.finish.unwrap_or(Finish::Pr)
+            };
+            finish(repo, mode)
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Applies the `[branch]` naming convention to `name`, adding the default prefix if it has none.
+fn branch_name(name: &str, config: &BranchConfig) -> Result<String, String> {
+    let name = name.trim();
+    let full = match &config.default_prefix {
+        Some(prefix) if !name.contains('/') => format!("{}/{}", prefix, name),
+        _ => name.to_string(),
+    };
+    if !config.prefixes.is_empty() {
+        let prefix = full.split_once('/').map(|(prefix, _)| prefix);
+        if !prefix.is_some_and(|p| config.prefixes.iter().any(|allowed| allowed == p)) {
+            return Err(format!(
+                "Branch '{}' does not follow the naming convention: use <prefix>/<name> with one of: {}.",
+                full,
+                config.prefixes.join(", ")
+            ));
+        }
+    }
+    if git::output(Path::new("."), &["check-ref-format", "--branch", &full]).is_none() {
+        return Err(format!("'{}' is not a valid branch name.", full));
+    }
+    Ok(full)
+}
+
+/// The branch work is started from and merged back into.
+pub fn default_branch(repo: &Path) -> Result<String, String> {
+    git::default_branch(repo)
+        .or_else(|| ["main", "master"].iter().find(|b| git::ref_exists(repo, &format!("refs/heads/{}", b))).map(|b| b.to_string()))
+        .ok_or_else(|| "Could not work out the default branch. Pass --from <branch>.".to_string())
+}
+
+fn new(repo: &Path, config: &BranchConfig, name: &str, from: Option<&str>, no_push: bool) -> Result<(), String> {
+    let name = branch_name(name, config)?;
+    if git::ref_exists(repo, &format!("refs/heads/{}", name)) {
+        return Err(format!("Branch '{}' already exists. Switch to it with 'odin branch switch {}'.", name, name));
+    }
+    let base = match from {
+        Some(from) => from.to_string(),
+        None => default_branch(repo)?,
+    };
+
+    let has_remote = git::remote_url(repo).is_some();
+    if has_remote {
+        println!("Fetching from remote...");
+        run_git(repo, &["fetch", "-q", "origin"], true).map_err(|e| format!("Failed to fetch from remote: {}", e))?;
+    }
+    let remote_base = format!("origin/{}", base);
+    let start = if has_remote && git::ref_exists(repo, &remote_base) { remote_base } else { base };
+
+    println!("Creating '{}' from {}...", name, start);
+    run_git(repo, &["switch", "-q", "--no-track", "-c", &name, &start], true)
+        .map_err(|e| format!("Failed to create branch '{}': {}", name, e))?;
+    if !has_remote || no_push {
+        println!("Switched to new branch '{}'.", name);
+        return Ok(());
+    }
+    if let Err(e) = run_git(repo, &["push", "-q", "-u", "origin", &name], true) {
+        return Err(format!("Created '{}' but failed to push it ({}). Push later with 'odin update'.", name, e));
+    }
+    println!("Switched to new branch '{}', tracking origin/{}.", name, name);
+    Ok(())
+}
+
+/// Local branches, then remote branches without a local copy, excluding the current one.
+fn branches(repo: &Path) -> Vec<(String, bool)> {
+    let list = |prefix: &str| -> Vec<String> {
+        git::output(repo, &["for-each-ref", "--format=%(refname:short)", prefix])
+            .map(|out| out.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+    let current = git::current_branch(repo);
+    let local = list("refs/heads");
+    let remote: Vec<String> = list("refs/remotes/origin")
+        .iter()
+        .filter_map(|r| r.strip_prefix("origin/"))
+        .filter(|r| *r != "HEAD" && !local.iter().any(|l| l == r))
+        .map(str::to_string)
+        .collect();
+    local
+        .into_iter()
+        .map(|b| (b, false))
+        .chain(remote.into_iter().map(|b| (b, true)))
+        .filter(|(b, _)| current.as_deref() != Some(b.as_str()))
+        .collect()
+}
+
+fn switch(repo: &Path, query: Option<&str>) -> Result<(), String> {
+    let branches = branches(repo);
+    if branches.is_empty() {
+        return Err("There are no other branches to switch to.".to_string());
+    }
+
+    let matching: Vec<&(String, bool)> = match query {
+        Some(query) => {
+            let query = query.to_lowercase();
+            match branches.iter().find(|(b, _)| b.to_lowercase() == query) {
+                Some(exact) => vec![exact],
+                None => branches.iter().filter(|(b, _)| b.to_lowercase().contains(&query)).collect(),
+            }
+        }
+        None => branches.iter().collect(),
+    };
+    let target = if matching.len() == 1 {
+        matching[0].0.clone()
+    } else {
+        require_terminal("Switch to branch", "a name that matches exactly one branch")?;
+        let labels: Vec<String> = branches
+            .iter()
+            .map(|(b, remote)| if *remote { format!("{} (remote)", b) } else { b.clone() })
+            .collect();
+        let Some(selected) = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Switch to branch")
+            .items(&labels)
+            .with_initial_text(query.unwrap_or_default())
+            .default(0)
+            .interact_opt()
+            .unwrap()
+        else {
+            println!("Cancelled.");
+            return Ok(());
+        };
+        branches[selected].0.clone()
+    };
+
+    run_git(repo, &["switch", "-q", &target], true).map_err(|e| format!("Failed to switch to '{}': {}", target, e))?;
+    println!("Switched to '{}'.", target);
+    Ok(())
+}
+
+fn finish(repo: &Path, mode: Finish) -> Result<(), String> {
+    let Some(branch) = git::current_branch(repo) else {
+        return Err("Not on a branch (detached HEAD).".to_string());
+    };
+    let default = default_branch(repo)?;
+    if branch == default {
+        return Err(format!("'{}' is the default branch; switch to the branch you want to finish.", branch));
+    }
+    if git::status(repo).is_some_and(|s| s.staged + s.unstaged + s.conflicted > 0) {
+        return Err("You have uncommitted changes. Commit them with 'odin update' first.".to_string());
+    }
+    let has_remote = git::remote_url(repo).is_some();
+    if has_remote {
+        run_git(repo, &["fetch", "-q", "origin"], true).map_err(|e| format!("Failed to fetch from remote: {}", e))?;
+    }
+
+    match mode {
+        Finish::Pr => {
+            if !has_remote {
+                return Err("This project has no remote to open a pull request on. Use --merge.".to_string());
+            }
+            open_pr(repo, &branch, &default)
+        }
+        Finish::Merge => merge_back(repo, &branch, &default, has_remote),
+    }
+}
+
+/// Opens a pull request for `branch`, or cleans up if its pull request has already been merged.
+fn open_pr(repo: &Path, branch: &str, default: &str) -> Result<(), String> {
+    crate::commands::ensure_gh_installed();
+    let existing = github::output(repo, &["pr", "view", branch, "--json", "state,headRefOid,url", "-q", ".state + \" \" + .headRefOid + \" \" + .url"]);
+    let mut fields = existing.as_deref().unwrap_or_default().splitn(3, ' ');
+    if let (Some("MERGED"), Some(head), Some(url)) = (fields.next(), fields.next(), fields.next()) {
+        println!("Pull request {} has been merged.", url);
+        // The branch is force-deleted below, so it must hold nothing beyond what was merged
+        let Some(unmerged) = git::count(repo, &format!("{}..{}", head, branch)) else {
+            return Err(format!(
+                "Could not compare '{}' with the merged pull request, so it was kept. Delete it with 'git branch -D {}' once you've checked it.",
+                branch, branch
+            ));
+        };
+        if unmerged > 0 {
+            return Err(format!(
+                "'{}' has {} commit{} that the merged pull request doesn't, so it was kept. Move them to a new branch, or delete '{}' with 'git branch -D {}'.",
+                branch,
+                unmerged,
+                plural(unmerged),
+                branch,
+                branch
+            ));
+        }
+        switch_to_default(repo, default, true)?;
+        delete_branch(repo, branch, true);
+        return Ok(());
+    }
+
+    super::pr::create(repo, branch, default, false)?;
+    println!("Once it is merged, run 'odin branch finish' again to delete the branch.");
+    Ok(())
+}
+
+/// Merges `branch` into the default branch, pushes it, and deletes `branch` everywhere.
+fn merge_back(repo: &Path, branch: &str, default: &str, has_remote: bool) -> Result<(), String> {
+    switch_to_default(repo, default, has_remote)?;
+    println!("Merging '{}' into '{}'...", branch, default);
+    if run_git(repo, &["merge", "-q", "--no-ff", "--no-edit", branch], true).is_err() {
+        let _ = run_git(repo, &["merge", "--abort"], true);
+        let _ = run_git(repo, &["switch", "-q", branch], true);
+        return Err(format!(
+            "Merging '{}' into '{}' failed, so it was undone. Bring '{}' up to date first, e.g. with 'git rebase {}'.",
+            branch, default, branch, default
+        ));
+    }
+    if has_remote {
+        println!("Pushing '{}'...", default);
+        if let Err(e) = run_git(repo, &["push", "-q", "origin", default], true) {
+            return Err(format!("Merged, but failed to push '{}' ({}). '{}' was kept.", default, e, branch));
+        }
+    }
+    delete_branch(repo, branch, false);
+    Ok(())
+}
+
+/// Checks out the default branch and fast-forwards it to the remote.
+fn switch_to_default(repo: &Path, default: &str, has_remote: bool) -> Result<(), String> {
+    run_git(repo, &["switch", "-q", default], true).map_err(|e| format!("Failed to switch to '{}': {}", default, e))?;
+    let remote_default = format!("origin/{}", default);
+    if has_remote && git::ref_exists(repo, &remote_default) && run_git(repo, &["merge", "-q", "--ff-only", &remote_default], true).is_err() {
+        return Err(format!("'{}' has diverged from {}. Sync it with 'odin update' first.", default, remote_default));
+    }
+    Ok(())
+}
+
+/// Deletes `branch` on the remote and locally. The remote goes first so git
+/// checks the local delete against the merge, not the stale upstream. `force`
+/// is for branches merged on GitHub, where squash or rebase merges leave
+/// commits git doesn't recognise as merged.
+fn delete_branch(repo: &Path, branch: &str, force: bool) {
+    if git::ref_exists(repo, &format!("refs/remotes/origin/{}", branch)) {
+        match run_git(repo, &["push", "-q", "origin", "--delete", branch], true) {
+            Ok(()) => println!("Deleted remote branch 'origin/{}'.", branch),
+            Err(e) => eprintln!("Failed to delete remote branch 'origin/{}': {}", branch, e),
+        }
+    }
+    match run_git(repo, &["branch", "-q", if force { "-D" } else { "-d" }, branch], true) {
+        Ok(()) => println!("Deleted local branch '{}'.", branch),
+        Err(e) => eprintln!("Failed to delete local branch '{}': {}", branch, e),
+    }
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::Path;
+use regex::Regex;
+use crate::git;
+
+const CHANGELOG: &str = "CHANGELOG.md";
+const UNRELEASED: &str = "## Unreleased";
+
+/// Sections in the order they are rendered.
+const SECTIONS: &[(Kind, &str)] = &[
+    (Kind::Breaking, "Breaking Changes"),
+    (Kind::Feature, "Features"),
+    (Kind::Fix, "Bug Fixes"),
+    (Kind::Other, "Other Changes"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Breaking,
+    Feature,
+    Fix,
+    Other,
+}
+
+/// One commit, as it appears in the changelog.
+struct Entry {
+    kind: Kind,
+    scope: Option<String>,
+    text: String,
+    hash: String,
+}
+
+pub fn run(since: Option<&str>, write: bool) {
+    let repo = Path::new(".");
+    if git::output(repo, &["rev-parse", "--git-dir"]).is_none() {
+        eprintln!("Not in a Git repository.");
+        std::process::exit(1);
+    }
+    let since = since.map(str::to_string).or_else(|| previous_tag(repo));
+    if let Some(tag) = &since
+        && !git::ref_exists(repo, tag)
+    {
+        eprintln!("'{}' is not a tag or commit in this repository.", tag);
+        std::process::exit(1);
+    }
+
+    let section = format!("{}\n\n{}", UNRELEASED, notes(repo, since.as_deref()));
+    if !write {
+        println!("{}", section);
+        return;
+    }
+    if let Err(e) = prepend(repo, &section) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    println!("Added the changes since {} to {}.", since.as_deref().unwrap_or("the first commit"), CHANGELOG);
+}
+
+/// The most recent `v`-prefixed version tag reachable from HEAD.
+pub fn previous_tag(repo: &Path) -> Option<String> {
+    git::output(repo, &["describe", "--tags", "--abbrev=0", "--match", "v[0-9]*"])
+}
+
+/// Markdown for the commits since `since` (or all commits), grouped by
+/// Conventional Commit type. Release commits made by `odin release` are left out.
+pub fn notes(repo: &Path, since: Option<&str>) -> String {
+    let entries = entries(repo, since);
+    if entries.is_empty() {
+        return "No changes since the previous release.\n".to_string();
+    }
+    let mut out = String::new();
+    for (kind, title) in SECTIONS {
+        let lines: Vec<String> = entries
+            .iter()
+            .filter(|e| e.kind == *kind)
+            .map(|e| match &e.scope {
+                Some(scope) => format!("- **{}:** {} ({})", scope, e.text, e.hash),
+                None => format!("- {} ({})", e.text, e.hash),
+            })
+            .collect();
+        if !lines.is_empty() {
+            out.push_str(&format!("### {}\n\n{}\n\n", title, lines.join("\n")));
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Adds `section` above the previous entries of CHANGELOG.md, creating it if needed.
+/// An `## Unreleased` section at the top is replaced, so writing twice doesn't repeat it.
+pub fn prepend(repo: &Path, section: &str) -> Result<(), String> {
+    let path = repo.join(CHANGELOG);
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let start = first_section(&existing);
+    let end = unreleased_end(&existing, start).unwrap_or(start);
+    // Keep a leading `# Changelog` title (and anything before the first entry) on top
+    let (head, rest) = (&existing[..start], &existing[end..]);
+    let head = if head.trim().is_empty() { "# Changelog\n\n".to_string() } else { format!("{}\n\n", head.trim_end()) };
+    let rest = if rest.is_empty() { String::new() } else { format!("\n{}", rest) };
+    fs::write(&path, format!("{}{}{}", head, section, rest)).map_err(|e| format!("Failed to write {}: {}", CHANGELOG, e))
+}
+
+/// Records a release in CHANGELOG.md as a `## <tag>` section with `notes`, the same
+/// notes the GitHub release gets. It takes the place of an `## Unreleased` section,
+/// which may be missing commits made after it was written.
+pub fn add_release(repo: &Path, tag: &str, notes: &str) -> Result<(), String> {
+    prepend(repo, &format!("## {}\n\n{}", tag, notes))
+}
+
+/// If the section at `start` is `## Unreleased`, the offset where it ends.
+fn unreleased_end(content: &str, start: usize) -> Option<usize> {
+    let section = &content[start..];
+    if section.lines().next() != Some(UNRELEASED) {
+        return None;
+    }
+    Some(section[1..].find("\n## ").map_or(content.len(), |i| start + i + 2))
+}
+
+/// Offset of the first `## ` heading, or the end of `content` if it has none.
+fn first_section(content: &str) -> usize {
+    if content.starts_with("## ") {
+        0
+    } else {
+        content.find("\n## ").map_or(content.len(), |i| i + 1)
+    }
+}
+
+fn entries(repo: &Path, since: Option<&str>) -> Vec<Entry> {
+    let range = since.map_or("HEAD".to_string(), |t| format!("{}..HEAD", t));
+    let log = git::output(repo, &["log", "--no-merges", "--format=%h%x1f%s%x1f%b%x1e", &range]).unwrap_or_default();
+    let header = Regex::new(r"^(?P<type>[a-z]+)(\((?P<scope>[^()]+)\))?(?P<bang>!)?: (?P<subject>.+)$").unwrap();
+    let footer = Regex::new(r"(?m)^BREAKING[ -]CHANGE: (.+)$").unwrap();
+    let release = Regex::new(r"^Release v\d").unwrap();
+
+    log.split('\x1e')
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut fields = record.splitn(3, '\x1f');
+            let hash = fields.next()?.to_string();
+            let subject = fields.next()?.trim();
+            let body = fields.next().unwrap_or_default();
+            if release.is_match(subject) {
+                return None;
+            }
+            let Some(caps) = header.captures(subject) else {
+                return Some(Entry { kind: Kind::Other, scope: None, text: subject.to_string(), hash });
+            };
+            let note = footer.captures(body).map(|c| c[1].trim().to_string());
+            let kind = match &caps["type"] {
+                _ if caps.name("bang").is_some() || note.is_some() => Kind::Breaking,
+                "feat" => Kind::Feature,
+                "fix" => Kind::Fix,
+                _ => Kind::Other,
+            };
+            Some(Entry {
+                kind,
+                scope: caps.name("scope").map(|s| s.as_str().to_string()),
+                text: note.unwrap_or_else(|| caps["subject"].to_string()),
+                hash,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,276 @@
+use std::fs;
+use std::path::Path;
+use clap::{Subcommand, ValueEnum};
+use super::branch;
+use crate::git;
+use crate::github;
+
+#[derive(Subcommand)]
+pub enum CiAction {
+    /// Write a GitHub Actions workflow for the project's language
+    Init {
+        /// Language to build and test (detected from the project files if not given)
+        #[arg(long, value_enum)]
+        lang: Option<Lang>,
+        /// Also write a release workflow that attaches <name>-<os>-<arch> binaries to each release
+        #[arg(long)]
+        release: bool,
+        /// Overwrite existing workflow files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the latest run of each workflow
+    Status {
+        /// Only show runs on this branch
+        #[arg(long)]
+        branch: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    Python,
+    Node,
+}
+
+const WORKFLOWS: &str = ".github/workflows";
+
+const RUST_CI: &str = r#"name: CI
+
+on:
+  push:
+    branches: [{branch}]
+  pull_request:
+
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+        with:
+          components: clippy
+      - uses: Swatinem/rust-cache@v2
+      - run: cargo build --workspace
+      - run: cargo clippy --workspace --all-targets -- -D warnings
+      - run: cargo test --workspace
+"#;
+
+const PYTHON_CI: &str = r#"name: CI
+
+on:
+  push:
+    branches: [{branch}]
+  pull_request:
+
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    strategy:
+      matrix:
+        python-version: ["3.10", "3.11", "3.12"]
+    steps:
+      - uses: actions/checkout@v4
+      - uses: actions/setup-python@v5
+        with:
+          python-version: ${{ matrix.python-version }}
+          cache: pip
+      - run: python -m pip install --upgrade pip
+      - run: {install}
+      - run: pytest
+"#;
+
+const NODE_CI: &str = r#"name: CI
+
+on:
+  push:
+    branches: [{branch}]
+  pull_request:
+
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    strategy:
+      matrix:
+        node-version: [20, 22]
+    steps:
+      - uses: actions/checkout@v4
+      - uses: actions/setup-node@v4
+        with:
+          node-version: ${{ matrix.node-version }}{cache}
+      - run: {install}
+      - run: npm run build --if-present
+      - run: npm test --if-present
+"#;
+
+/// Builds a binary per platform on each version tag and attaches it to the
+/// release under the `<name>-<os>-<arch>` names `odin upgrade` looks for.
+const RUST_RELEASE: &str = r#"name: Release
+
+on:
+  push:
+    tags: ["v*"]
+
+permissions:
+  contents: write
+
+jobs:
+  build:
+    strategy:
+      matrix:
+        include:
+          - runner: ubuntu-latest
+            asset: {bin}-linux-x86_64
+          - runner: ubuntu-24.04-arm
+            asset: {bin}-linux-aarch64
+          - runner: macos-13
+            asset: {bin}-macos-x86_64
+          - runner: macos-latest
+            asset: {bin}-macos-aarch64
+    runs-on: ${{ matrix.runner }}
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+      - run: cargo build --release
+      - run: cp target/release/{bin} ${{ matrix.asset }}
+      - name: Attach to the release
+        env:
+          GH_TOKEN: ${{ github.token }}
+        run: |
+          gh release view "$GITHUB_REF_NAME" > /dev/null 2>&1 || gh release create "$GITHUB_REF_NAME" --verify-tag --generate-notes || true
+          gh release upload "$GITHUB_REF_NAME" "${{ matrix.asset }}" --clobber
+"#;
+
+pub fn run(action: &CiAction) {
+    let repo = Path::new(".");
+    let result = match action {
+        CiAction::Init { lang, release, force } => init(repo, *lang, *release, *force),
+        CiAction::Status { branch } => status(repo, branch.as_deref()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn detect(repo: &Path) -> Option<Lang> {
+    if repo.join("Cargo.toml").is_file() {
+        Some(Lang::Rust)
+    } else if ["pyproject.toml", "setup.py", "requirements.txt"].iter().any(|f| repo.join(f).is_file()) {
+        Some(Lang::Python)
+    } else if repo.join("package.json").is_file() {
+        Some(Lang::Node)
+    } else {
+        None
+    }
+}
+
+fn init(repo: &Path, lang: Option<Lang>, release: bool, force: bool) -> Result<(), String> {
+    let Some(root) = git::output(repo, &["rev-parse", "--show-toplevel"]) else {
+        return Err("Not in a Git repository.".to_string());
+    };
+    let root = Path::new(&root);
+    let lang = lang
+        .or_else(|| detect(root))
+        .ok_or_else(|| "Could not detect the project's language. Pass --lang rust, python or node.".to_string())?;
+    if release && lang != Lang::Rust {
+        return Err("The release workflow builds Rust binaries; it isn't available for Python or Node projects.".to_string());
+    }
+    let default_branch = branch::default_branch(root).unwrap_or_else(|_| "main".to_string());
+
+    let mut files = vec![("ci.yml", ci_workflow(root, lang).replace("{branch}", &default_branch))];
+    if release {
+        files.push(("release.yml", RUST_RELEASE.replace("{bin}", &binary_name(root)?)));
+    }
+    let dir = root.join(WORKFLOWS);
+    for (name, _) in &files {
+        if dir.join(name).exists() && !force {
+            return Err(format!("{}/{} already exists. Pass --force to overwrite it.", WORKFLOWS, name));
+        }
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", WORKFLOWS, e))?;
+    for (name, content) in &files {
+        fs::write(dir.join(name), content).map_err(|e| format!("Failed to write {}/{}: {}", WORKFLOWS, name, e))?;
+        println!("Wrote {}/{}", WORKFLOWS, name);
+    }
+    println!("Commit and push them with 'odin update' to start running them.");
+    Ok(())
+}
+
+fn ci_workflow(root: &Path, lang: Lang) -> String {
+    match lang {
+        Lang::Rust => RUST_CI.to_string(),
+        Lang::Python => {
+            let install = if root.join("pyproject.toml").is_file() || root.join("setup.py").is_file() {
+                "pip install . pytest"
+            } else {
+                "pip install -r requirements.txt pytest"
+            };
+            PYTHON_CI.replace("{install}", install)
+        }
+        Lang::Node => {
+            // `npm ci` and the npm cache both need a lockfile
+            let locked = root.join("package-lock.json").is_file();
+            NODE_CI
+                .replace("{cache}", if locked { "\n          cache: npm" } else { "" })
+                .replace("{install}", if locked { "npm ci" } else { "npm install" })
+        }
+    }
+}
+
+/// The binary `cargo build` produces: the first `[[bin]]` name, else the package name.
+fn binary_name(root: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(root.join("Cargo.toml")).map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
+    let manifest: toml::Table = content.parse().map_err(|e| format!("Invalid Cargo.toml: {}", e))?;
+    manifest
+        .get("bin")
+        .and_then(|bins| bins.get(0)?.get("name")?.as_str())
+        .or_else(|| manifest.get("package")?.get("name")?.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "Cargo.toml has no [package] name; the release workflow needs a binary.".to_string())
+}
+
+fn status(repo: &Path, branch: Option<&str>) -> Result<(), String> {
+    if git::remote_url(repo).is_none() {
+        return Err("This project has no remote. Push it to GitHub first with 'odin update'.".to_string());
+    }
+    crate::commands::ensure_gh_installed();
+    let mut args = vec!["run", "list", "--limit", "50", "--json", "workflowName,status,conclusion,headBranch,displayTitle,createdAt"];
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    let json = github::output(repo, &args).ok_or_else(|| "Could not list workflow runs.".to_string())?;
+    let runs: Vec<serde_json::Value> = serde_json::from_str(&json).map_err(|e| format!("Unexpected output from gh: {}", e))?;
+
+    // Runs come newest first; keep the first one of each workflow
+    let mut latest: Vec<&serde_json::Value> = Vec::new();
+    for run in &runs {
+        if !latest.iter().any(|l| l["workflowName"] == run["workflowName"]) {
+            latest.push(run);
+        }
+    }
+    if latest.is_empty() {
+        println!("No workflow runs yet. Add a workflow with 'odin ci init'.");
+        return Ok(());
+    }
+
+    let text = |run: &serde_json::Value, key: &str| run[key].as_str().unwrap_or_default().to_string();
+    let rows: Vec<[String; 5]> = latest
+        .iter()
+        .map(|run| {
+            let result = match text(run, "conclusion") {
+                conclusion if conclusion.is_empty() => text(run, "status").replace('_', " "),
+                conclusion => conclusion,
+            };
+            let created = text(run, "createdAt").replace('T', " ").chars().take(16).collect();
+            [text(run, "workflowName"), result, text(run, "headBranch"), created, text(run, "displayTitle")]
+        })
+        .collect();
+    let width = |i: usize| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0);
+    let (name_width, result_width, branch_width) = (width(0), width(1), width(2));
+    for [name, result, head, created, title] in &rows {
+        println!("{:<name_width$}  {:<result_width$}  {:<branch_width$}  {}  {}", name, result, head, created, title);
+    }
+    Ok(())
+}
@@ -9,7 +9,7 @@ pub fn run() {
 
     // Run gh auth login to authenticate with GitHub
     let status = Command::new("gh")
-        .args(&["auth", "login"])
+        .args(["auth", "login"])
         .status();
 
     match status {
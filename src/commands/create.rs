@@ -2,19 +2,14 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 use std::process::Command;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize)]
-struct Project {
-    name: String,
-    path: String,
-}
+use crate::git;
+use crate::registry::{self, Project};
 
 pub fn run(project_name: &str) {
     crate::commands::ensure_gh_installed();
 
     // Check GitHub auth
-    let auth_check = Command::new("gh").args(&["auth", "status"]).output();
+    let auth_check = Command::new("gh").args(["auth", "status"]).output();
     if !auth_check.map(|o| o.status.success()).unwrap_or(false) {
         println!("GitHub not configured. Please run 'odin config' first.");
         return;
@@ -56,7 +51,7 @@ pub fn run(project_name: &str) {
 
     // Change to the directory and initialize git
     if let Err(e) = Command::new("git")
-        .args(&["init"])
+        .args(["init"])
         .current_dir(project_path)
         .status()
     {
@@ -71,7 +66,7 @@ pub fn run(project_name: &str) {
         return;
     }
     if let Err(e) = Command::new("git")
-        .args(&["add", "README.md"])
+        .args(["add", "README.md"])
         .current_dir(project_path)
         .status()
     {
@@ -79,7 +74,7 @@ pub fn run(project_name: &str) {
         return;
     }
     if let Err(e) = Command::new("git")
-        .args(&["commit", "-m", "Initial commit"])
+        .args(["commit", "-m", "Initial commit"])
         .current_dir(project_path)
         .status()
     {
@@ -89,7 +84,7 @@ pub fn run(project_name: &str) {
 
     // Create GitHub repo using gh CLI
     let output = Command::new("gh")
-        .args(&["repo", "create", project_name, "--public", "--source=.", "--remote=origin", "--push"])
+        .args(["repo", "create", project_name, "--public", "--source=.", "--remote=origin", "--push"])
         .current_dir(project_path)
         .output();
 
@@ -106,26 +101,11 @@ pub fn run(project_name: &str) {
     }
 
     // Add to projects.json
-    let odin_dir = dirs::home_dir().unwrap().join(".odin");
-    if let Err(e) = fs::create_dir_all(&odin_dir) {
-        eprintln!("Failed to create ~/.odin: {}", e);
-        return;
+    let project = Project::new(project_name, &abs_path.display().to_string())
+        .with_remote(git::remote_url(&abs_path));
+    match registry::add(project) {
+        Ok(()) => println!("Project added to global list."),
+        Err(e) => eprintln!("{}", e),
     }
-    let projects_file = odin_dir.join("projects.json");
-    let mut projects: Vec<Project> = if projects_file.exists() {
-        let data = fs::read_to_string(&projects_file).unwrap_or_else(|_| "[]".to_string());
-        serde_json::from_str(&data).unwrap_or_default()
-    } else {
-        Vec::new()
-    };
-    projects.push(Project {
-        name: project_name.to_string(),
-        path: abs_path.display().to_string(),
-    });
-    let json = serde_json::to_string_pretty(&projects).unwrap();
-    if let Err(e) = fs::write(&projects_file, json) {
-        eprintln!("Failed to update projects.json: {}", e);
-    } else {
-        println!("Project added to global list.");
-    }
-}
\ No newline at end of file
+    println!("Once the project has a Cargo.toml, pyproject.toml or package.json, add CI with 'odin ci init'.");
+}
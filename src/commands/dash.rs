@@ -0,0 +1,265 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use super::open::launch_editor;
+use crate::git;
+use crate::github;
+use crate::registry::{self, Filter, Project};
+
+struct Entry {
+    project: Project,
+    status: Option<git::Status>,
+    details: Option<Details>,
+}
+
+struct Details {
+    commits: Vec<String>,
+    open_prs: Option<usize>,
+    config: Vec<String>,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    state: TableState,
+    message: String,
+}
+
+/// What the event loop asks `run` to do once the terminal has been released.
+enum Exit {
+    Quit,
+    Cd(String),
+}
+
+pub fn run(filter: &Filter) {
+    let projects = filter.apply(registry::load());
+    if projects.is_empty() {
+        println!("No projects found. Create a project first with 'odin create <name>'.");
+        return;
+    }
+
+    let mut app = App {
+        entries: projects
+            .into_iter()
+            .map(|project| Entry { project, status: None, details: None })
+            .collect(),
+        state: TableState::default().with_selected(Some(0)),
+        message: "q quit  r refresh  u update  p pull  e editor  enter cd".to_string(),
+    };
+    app.refresh();
+
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+
+    match result {
+        Ok(Exit::Cd(path)) => println!("To change to this directory, run: cd {}", path),
+        Ok(Exit::Quit) => {}
+        Err(e) => eprintln!("Dashboard error: {}", e),
+    }
+}
+
+impl App {
+    fn refresh(&mut self) {
+        for entry in &mut self.entries {
+            entry.status = git::status(Path::new(&entry.project.path));
+            entry.details = None;
+        }
+    }
+
+    fn selected(&self) -> usize {
+        self.state.selected().unwrap_or(0)
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<Exit> {
+        loop {
+            let selected = self.selected();
+            if self.entries[selected].details.is_none() {
+                self.entries[selected].details = Some(load_details(&self.entries[selected].project));
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let path = self.entries[selected].project.path.clone();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Exit::Quit),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.state.select(Some((selected + 1).min(self.entries.len() - 1)));
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.state.select(Some(selected.saturating_sub(1)));
+                }
+                KeyCode::Enter | KeyCode::Char('c') => return Ok(Exit::Cd(path)),
+                KeyCode::Char('r') => {
+                    self.refresh();
+                    self.message = "Refreshed.".to_string();
+                }
+                KeyCode::Char('p') => {
+                    self.message = pull(&path);
+                    self.refresh();
+                }
+                KeyCode::Char('u') => {
+                    *terminal = suspend(true, || {
+                        let exe = std::env::current_exe()?;
+                        Command::new(exe).arg("update").current_dir(&path).status()?;
+                        Ok(())
+                    })?;
+                    self.refresh();
+                    self.message = format!("Ran update in {}.", self.entries[selected].project.name);
+                }
+                KeyCode::Char('e') => {
+                    *terminal = suspend(false, || launch_editor(Path::new(&path)))?;
+                    self.refresh();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [list, detail] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+
+        let rows = self.entries.iter().map(|entry| {
+            let (branch, sync, changes) = match &entry.status {
+                Some(status) => (
+                    status.branch.clone().unwrap_or_else(|| "(detached)".to_string()),
+                    match &status.upstream {
+                        Some(_) => format!("+{} -{}", status.ahead, status.behind),
+                        None => "no upstream".to_string(),
+                    },
+                    if status.is_dirty() {
+                        format!("{}s {}m {}?", status.staged, status.unstaged, status.untracked)
+                    } else {
+                        "clean".to_string()
+                    },
+                ),
+                None if entry.project.is_missing() => ("missing".to_string(), String::new(), String::new()),
+                None => ("not a git repo".to_string(), String::new(), String::new()),
+            };
+            let style = match &entry.status {
+                Some(status) if status.is_dirty() => Style::default().fg(Color::Yellow),
+                Some(_) => Style::default(),
+                None => Style::default().fg(Color::Red),
+            };
+            Row::new(vec![
+                Cell::from(entry.project.name.clone()),
+                Cell::from(branch),
+                Cell::from(sync),
+                Cell::from(changes),
+            ])
+            .style(style)
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Percentage(35), Constraint::Percentage(25), Constraint::Percentage(20), Constraint::Percentage(20)],
+        )
+        .header(Row::new(vec!["Project", "Branch", "Sync", "Changes"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title("Projects"))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, list, &mut self.state);
+
+        let entry = &self.entries[self.selected()];
+        let mut lines = vec![
+            Line::from(format!("Path: {}", entry.project.path)),
+            Line::from(format!("Remote: {}", entry.project.remote.as_deref().unwrap_or("-"))),
+        ];
+        if let Some(group) = &entry.project.group {
+            lines.push(Line::from(format!("Group: {}", group)));
+        }
+        if !entry.project.tags.is_empty() {
+            lines.push(Line::from(format!("Tags: {}", entry.project.tags.join(", "))));
+        }
+        if let Some(details) = &entry.details {
+            let prs = details.open_prs.map_or("n/a".to_string(), |n| n.to_string());
+            lines.push(Line::from(format!("Open PRs: {}", prs)));
+            lines.push(Line::from(""));
+            lines.push(Line::from("Recent commits:").style(Style::default().add_modifier(Modifier::BOLD)));
+            lines.extend(details.commits.iter().map(|c| Line::from(format!("  {}", c))));
+            if !details.config.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from("CONFIG.toml:").style(Style::default().add_modifier(Modifier::BOLD)));
+                lines.extend(details.config.iter().map(|c| Line::from(format!("  {}", c))));
+            }
+        }
+        let detail_pane = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(entry.project.name.clone()))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(detail_pane, detail);
+
+        frame.render_widget(Paragraph::new(self.message.clone()), footer);
+    }
+}
+
+fn load_details(project: &Project) -> Details {
+    let dir = Path::new(&project.path);
+    let commits = git::output(dir, &["log", "-5", "--format=%h %s (%cr)"])
+        .map(|out| out.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let open_prs = github::output(dir, &["pr", "list", "--state", "open", "--json", "number"])
+        .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(&json).ok())
+        .map(|prs| prs.len());
+
+    Details { commits, open_prs, config: config_summary(dir) }
+}
+
+/// Flattens CONFIG.toml into `section.key = value` lines.
+fn config_summary(dir: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(dir.join("CONFIG.toml")) else {
+        return Vec::new();
+    };
+    let Ok(table) = content.parse::<toml::Table>() else {
+        return vec!["(invalid TOML)".to_string()];
+    };
+    let mut lines = Vec::new();
+    for (section, value) in &table {
+        match value {
+            toml::Value::Table(inner) => {
+                for (key, value) in inner {
+                    lines.push(format!("{}.{} = {}", section, key, value));
+                }
+            }
+            other => lines.push(format!("{} = {}", section, other)),
+        }
+    }
+    lines
+}
+
+fn pull(path: &str) -> String {
+    let output = Command::new("git").args(["pull", "--ff-only"]).current_dir(path).output();
+    match output {
+        Ok(o) if o.status.success() => "Pulled from remote.".to_string(),
+        Ok(o) => format!("Pull failed: {}", String::from_utf8_lossy(&o.stderr).lines().next().unwrap_or("")),
+        Err(e) => format!("Error running git pull: {}", e),
+    }
+}
+
+/// Hands the terminal to `action` and takes it back afterwards, optionally
+/// waiting for Enter so the user can read the output first.
+fn suspend(pause: bool, action: impl FnOnce() -> io::Result<()>) -> io::Result<DefaultTerminal> {
+    ratatui::restore();
+    let result = action();
+    if let Err(e) = &result {
+        eprintln!("{}", e);
+    }
+    if !pause && result.is_ok() {
+        return Ok(ratatui::init());
+    }
+    print!("\nPress Enter to return to the dashboard...");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(ratatui::init())
+}
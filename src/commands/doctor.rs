@@ -0,0 +1,106 @@
+use std::path::Path;
+use std::process::Command;
+use super::plural;
+use crate::git;
+use crate::registry::{self, Project};
+
+/// Clones a missing project back into its recorded path from its recorded remote.
+pub fn reclone(project: &Project) -> Result<(), String> {
+    let Some(remote) = &project.remote else {
+        return Err(format!("No remote recorded for '{}'.", project.name));
+    };
+    git::clone(remote, Path::new(&project.path))
+}
+
+/// Non-interactive health check. Exits with status 1 if problems remain.
+pub fn run(registry_only: bool, prune: bool, reclone_missing: bool) {
+    let mut problems = 0;
+
+    if !registry_only {
+        problems += check_tools();
+    }
+    problems += check_registry(prune, reclone_missing);
+
+    if problems > 0 {
+        println!("{} problem{} found.", problems, plural(problems));
+        std::process::exit(1);
+    }
+    println!("No problems found.");
+}
+
+fn check_tools() -> usize {
+    let mut problems = 0;
+
+    let git_ok = Command::new("git").arg("--version").output().is_ok_and(|o| o.status.success());
+    if git_ok {
+        println!("ok      git is installed");
+    } else {
+        println!("error   git is not installed");
+        problems += 1;
+    }
+
+    let gh_ok = Command::new("gh").arg("--version").output().is_ok_and(|o| o.status.success());
+    if !gh_ok {
+        println!("error   GitHub CLI (gh) is not installed");
+        return problems + 1;
+    }
+    println!("ok      gh is installed");
+
+    let auth_ok = Command::new("gh").args(["auth", "status"]).output().is_ok_and(|o| o.status.success());
+    if auth_ok {
+        println!("ok      gh is authenticated");
+    } else {
+        println!("error   gh is not authenticated (run 'odin config')");
+        problems += 1;
+    }
+    problems
+}
+
+fn check_registry(prune: bool, reclone_missing: bool) -> usize {
+    if !registry::exists() {
+        println!("ok      no projects registered");
+        return 0;
+    }
+
+    let mut projects = registry::load();
+    let mut problems = 0;
+    let mut changed = false;
+
+    projects.retain(|project| {
+        if !project.is_missing() {
+            return true;
+        }
+        if reclone_missing && project.remote.is_some() {
+            match reclone(project) {
+                Ok(()) => {
+                    println!("fixed   {}: re-cloned into {}", project.name, project.path);
+                    return true;
+                }
+                Err(e) => println!("error   {}: re-clone failed: {}", project.name, e),
+            }
+        }
+        if prune {
+            println!("fixed   {}: removed missing directory {} from the list", project.name, project.path);
+            changed = true;
+            return false;
+        }
+        println!("missing {}: {}", project.name, project.path);
+        problems += 1;
+        true
+    });
+
+    for project in &projects {
+        if !project.is_missing() && !Path::new(&project.path).join(".git").exists() {
+            println!("warning {}: {} is not a git repository", project.name, project.path);
+        }
+    }
+
+    if changed && let Err(e) = registry::save(&projects) {
+        eprintln!("{}", e);
+        problems += 1;
+    }
+    if problems == 0 {
+        println!("ok      registry ({} project{})", projects.len(), plural(projects.len()));
+    }
+    problems
+}
@@ -0,0 +1,142 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use super::plural;
+use crate::git;
+use crate::registry::{self, Filter, Project};
+
+pub struct Options {
+    pub parallel: usize,
+    pub fail_fast: bool,
+    pub only_dirty: bool,
+}
+
+enum Outcome {
+    Exited(Option<i32>, Duration),
+    Failed(String),
+    Skipped,
+}
+
+pub fn run(filter: &Filter, options: &Options, command: &[String]) {
+    let Some((program, args)) = command.split_first() else {
+        eprintln!("No command given. Usage: odin foreach [OPTIONS] -- <command>...");
+        return;
+    };
+
+    let mut projects: Vec<Project> = filter.apply(registry::load());
+    projects.retain(|p| {
+        if p.is_missing() {
+            eprintln!("Skipping '{}': directory {} is missing.", p.name, p.path);
+            return false;
+        }
+        true
+    });
+    if options.only_dirty {
+        projects.retain(|p| git::status(Path::new(&p.path)).is_some_and(|s| s.is_dirty()));
+    }
+    if projects.is_empty() {
+        println!("No matching projects.");
+        return;
+    }
+
+    let width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let results: Mutex<Vec<Option<Outcome>>> = Mutex::new(projects.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..options.parallel.max(1).min(projects.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(project) = projects.get(i) else {
+                    break;
+                };
+                let outcome = if stop.load(Ordering::SeqCst) {
+                    Outcome::Skipped
+                } else {
+                    let prefix = format!("[{:width$}]", project.name, width = width);
+                    run_one(project, program, args, &prefix)
+                };
+                let failed = !matches!(outcome, Outcome::Exited(Some(0), _) | Outcome::Skipped);
+                if failed && options.fail_fast {
+                    stop.store(true, Ordering::SeqCst);
+                }
+                results.lock().unwrap()[i] = Some(outcome);
+            });
+        }
+    });
+
+    let results = results.into_inner().unwrap();
+    println!();
+    println!("{:width$}  {:>6}  {:>9}", "Project", "Exit", "Duration", width = width.max(7));
+    let mut failures = 0;
+    for (project, outcome) in projects.iter().zip(results) {
+        let (exit, duration) = match outcome.unwrap_or(Outcome::Skipped) {
+            Outcome::Exited(Some(0), d) => ("0".to_string(), format!("{:.1}s", d.as_secs_f64())),
+            Outcome::Exited(code, d) => {
+                failures += 1;
+                (code.map_or("signal".to_string(), |c| c.to_string()), format!("{:.1}s", d.as_secs_f64()))
+            }
+            Outcome::Failed(e) => {
+                failures += 1;
+                eprintln!("{}: {}", project.name, e);
+                ("error".to_string(), "-".to_string())
+            }
+            Outcome::Skipped => ("skipped".to_string(), "-".to_string()),
+        };
+        println!("{:width$}  {:>6}  {:>9}", project.name, exit, duration, width = width.max(7));
+    }
+
+    if failures > 0 {
+        println!("{} of {} project{} failed.", failures, projects.len(), plural(projects.len()));
+        std::process::exit(1);
+    }
+}
+
+fn run_one(project: &Project, program: &str, args: &[String], prefix: &str) -> Outcome {
+    let started = Instant::now();
+    let child = Command::new(program)
+        .args(args)
+        .current_dir(&project.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return Outcome::Failed(format!("failed to start '{}': {}", program, e)),
+    };
+
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| stream(stdout, prefix, false));
+        scope.spawn(|| stream(stderr, prefix, true));
+    });
+
+    match child.wait() {
+        Ok(status) => Outcome::Exited(status.code(), started.elapsed()),
+        Err(e) => Outcome::Failed(e.to_string()),
+    }
+}
+
+/// Copies each line of `pipe` to our stdout or stderr, prefixed with the project name.
+fn stream(pipe: impl Read, prefix: &str, to_stderr: bool) {
+    // Lines are read as bytes so output that isn't UTF-8 doesn't stop the pipe being drained
+    let mut reader = BufReader::new(pipe);
+    let mut buffer = Vec::new();
+    while reader.read_until(b'\n', &mut buffer).is_ok_and(|n| n > 0) {
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if to_stderr {
+            eprintln!("{} {}", prefix, line);
+        } else {
+            println!("{} {}", prefix, line);
+        }
+        buffer.clear();
+    }
+}
@@ -0,0 +1,67 @@
+use clap::Subcommand;
+use crate::registry;
+
+#[derive(Subcommand)]
+pub enum GroupAction {
+    /// Put a project in a group, replacing its current group
+    Set {
+        /// Name of the project
+        project: String,
+        /// Group name (e.g. a client or team)
+        group: String,
+    },
+    /// Remove a project from its group
+    Clear {
+        /// Name of the project
+        project: String,
+    },
+    /// List groups and their projects
+    List,
+}
+
+pub fn run(action: &GroupAction) {
+    let mut projects = registry::load();
+
+    match action {
+        GroupAction::Set { project, group } => {
+            let Some(entry) = registry::find_by_name(&mut projects, project) else {
+                eprintln!("Project '{}' not found in the registry.", project);
+                return;
+            };
+            entry.group = Some(group.to_string());
+            match registry::save(&projects) {
+                Ok(()) => println!("Project '{}' is now in group '{}'.", project, group),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        GroupAction::Clear { project } => {
+            let Some(entry) = registry::find_by_name(&mut projects, project) else {
+                eprintln!("Project '{}' not found in the registry.", project);
+                return;
+            };
+            if entry.group.take().is_none() {
+                println!("Project '{}' is not in a group.", project);
+                return;
+            }
+            match registry::save(&projects) {
+                Ok(()) => println!("Removed '{}' from its group.", project),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        GroupAction::List => {
+            let mut groups: Vec<&String> = projects.iter().filter_map(|p| p.group.as_ref()).collect();
+            groups.sort_by_key(|t| t.to_lowercase());
+            groups.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+            if groups.is_empty() {
+                println!("No groups defined.");
+                return;
+            }
+            for group in groups {
+                println!("{}:", group);
+                for project in projects.iter().filter(|p| p.in_group(group)) {
+                    println!("  - {}", project.name);
+                }
+            }
+        }
+    }
+}
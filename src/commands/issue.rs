@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::Command;
+use clap::Subcommand;
+use dialoguer::{theme::ColorfulTheme, Input};
+use super::{open, require_terminal, OpenItems};
+use crate::git;
+use crate::registry::{self, Filter};
+
+/// Everything below this line in the editor file is ignored. A marker is used
+/// instead of `#` comments because issue bodies are Markdown.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+#[derive(Subcommand)]
+pub enum IssueAction {
+    /// List open issues in the current project or across the registry
+    List {
+        /// List issues in every registered project
+        #[arg(long)]
+        all_projects: bool,
+        /// List issues in projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// List issues in projects in this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Open a new issue in the current project
+    New {
+        /// Issue title (prompted for if not given)
+        #[arg(short, long)]
+        title: Option<String>,
+        /// Issue body
+        #[arg(short, long)]
+        body: Option<String>,
+        /// Write the title and body in $EDITOR
+        #[arg(short, long, conflicts_with_all = ["title", "body"])]
+        edit: bool,
+    },
+    /// Close an issue
+    Close {
+        /// Issue number
+        number: u32,
+        /// Leave a comment when closing
+        #[arg(short, long)]
+        comment: Option<String>,
+    },
+}
+
+pub fn run(action: &IssueAction) {
+    crate::commands::ensure_gh_installed();
+    let repo = Path::new(".");
+
+    let result = match action {
+        IssueAction::List { all_projects, tag, group } => {
+            let filter = Filter::new(tag.clone(), group.clone());
+            if *all_projects || !filter.is_empty() {
+                OPEN_ISSUES.list_projects(&filter.apply(registry::load()));
+                Ok(())
+            } else {
+                OPEN_ISSUES.list(repo)
+            }
+        }
+        IssueAction::New { title, body, edit } => new(repo, title.as_deref(), body.as_deref(), *edit),
+        IssueAction::Close { number, comment } => {
+            let number = number.to_string();
+            let mut args = vec!["issue", "close", number.as_str()];
+            if let Some(comment) = comment {
+                args.extend(["--comment", comment.as_str()]);
+            }
+            let status = Command::new("gh").args(&args).status();
+            if status.is_ok_and(|s| s.success()) {
+                Ok(())
+            } else {
+                Err(format!("Failed to close issue #{}.", number))
+            }
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+const OPEN_ISSUES: OpenItems = OpenItems {
+    kind: "issue",
+    name: "issues",
+    fields: "number,title,labels,author",
+    line: |issue| {
+        let labels: Vec<&str> = issue["labels"]
+            .as_array()
+            .map(|labels| labels.iter().filter_map(|l| l["name"].as_str()).collect())
+            .unwrap_or_default();
+        let labels = if labels.is_empty() { String::new() } else { format!(" [{}]", labels.join(", ")) };
+        format!(
+            "#{:<5} {}{} (@{})",
+            issue["number"].as_u64().unwrap_or(0),
+            issue["title"].as_str().unwrap_or_default(),
+            labels,
+            issue["author"]["login"].as_str().unwrap_or_default()
+        )
+    },
+};
+
+fn new(repo: &Path, title: Option<&str>, body: Option<&str>, edit: bool) -> Result<(), String> {
+    let (title, body) = if edit {
+        edit_issue(repo)?
+    } else {
+        let title = match title {
+            Some(title) => title.trim().to_string(),
+            None => prompt("Title", "--title <title> or --edit")?,
+        };
+        let body = match body {
+            Some(body) => body.to_string(),
+            None if !title.is_empty() && io::stdin().is_terminal() => prompt("Body (optional)", "")?,
+            None => String::new(),
+        };
+        (title, body)
+    };
+    if title.is_empty() {
+        return Err("Issue title cannot be empty.".to_string());
+    }
+
+    let output = Command::new("gh")
+        .args(["issue", "create", "--title", &title, "--body", &body])
+        .output()
+        .map_err(|e| format!("Error running gh command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to create issue: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    println!("Created issue: {}", String::from_utf8_lossy(&output.stdout).trim());
+    Ok(())
+}
+
+fn prompt(label: &str, flags: &str) -> Result<String, String> {
+    require_terminal(&format!("Issue {}", label.to_lowercase()), flags)?;
+    let value: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(label)
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+    Ok(value.trim().to_string())
+}
+
+/// Opens the editor on a scratch file; the first line is the title and the rest the body.
+fn edit_issue(repo: &Path) -> Result<(String, String), String> {
+    let git_dir = git::output(repo, &["rev-parse", "--git-dir"]).unwrap_or_else(|| ".git".to_string());
+    let path = repo.join(git_dir).join("ODIN_ISSUE_EDITMSG");
+    let content = format!("\n\n{}\n# The first line is the title, the rest is the body (Markdown).\n# Everything from the line above down is ignored.\n", SCISSORS);
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let status = open::editor_command(&path).status();
+    let edited = fs::read_to_string(&path).unwrap_or_default();
+    let _ = fs::remove_file(&path);
+    if !status.is_ok_and(|s| s.success()) {
+        return Err("Editor exited with an error. No issue was created.".to_string());
+    }
+    let text = edited.split(SCISSORS).next().unwrap_or_default().trim();
+    let (title, body) = text.split_once('\n').unwrap_or((text, ""));
+    Ok((title.trim().to_string(), body.trim().to_string()))
+}
@@ -1,25 +1,16 @@
-use std::fs;
-use std::io::{self, Write};
-use serde::{Deserialize, Serialize};
-use dialoguer::{theme::ColorfulTheme, Select};
+use std::path::Path;
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use super::doctor;
+use crate::git;
+use crate::registry::{self, Filter, Project};
 
-#[derive(Serialize, Deserialize)]
-struct Project {
-    name: String,
-    path: String,
-}
-
-pub fn run() {
-    let odin_dir = dirs::home_dir().unwrap().join(".odin");
-    let projects_file = odin_dir.join("projects.json");
-
-    if !projects_file.exists() {
+pub fn run(filter: &Filter) {
+    if !registry::exists() {
         println!("No projects found. Create a project first with 'odin create <name>'.");
         return;
     }
 
-    let data = fs::read_to_string(&projects_file).unwrap();
-    let mut projects: Vec<Project> = serde_json::from_str(&data).unwrap_or_default();
+    let mut projects = registry::load();
 
     if projects.is_empty() {
         println!("No projects found.");
@@ -27,43 +18,22 @@ pub fn run() {
     }
 
     // Check for missing directories
-    let mut missing: Vec<usize> = Vec::new();
-    for (i, project) in projects.iter().enumerate() {
-        if !std::path::Path::new(&project.path).exists() {
-            missing.push(i);
-        }
+    if projects.iter().any(Project::is_missing) {
+        repair_missing(&mut projects);
     }
 
-    if !missing.is_empty() {
-        println!("The following projects have missing directories:");
-        for &i in &missing {
-            println!("- {}: {}", projects[i].name, projects[i].path);
-        }
-        print!("Remove all missing projects from the list? (y/N): ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        if input.trim().eq_ignore_ascii_case("y") {
-            // Remove in reverse order to maintain indices
-            for &i in missing.iter().rev() {
-                projects.remove(i);
-            }
-            // Save updated projects
-            let json = serde_json::to_string_pretty(&projects).unwrap();
-            if let Err(e) = fs::write(&projects_file, json) {
-                eprintln!("Failed to update projects.json: {}", e);
-            } else {
-                println!("Removed missing projects from the list.");
-            }
-        }
-    }
+    let projects = filter.apply(projects);
 
     if projects.is_empty() {
-        println!("No projects found.");
+        if filter.is_empty() {
+            println!("No projects found.");
+        } else {
+            println!("No projects match the given tag/group.");
+        }
         return;
     }
 
-    let items: Vec<String> = projects.iter().map(|p| format!("{} - {}", p.name, p.path)).collect();
+    let items: Vec<String> = projects.iter().map(describe).collect();
 
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Select a project to work on")
@@ -75,4 +45,83 @@ pub fn run() {
     let selected_project = &projects[selection];
     println!("Selected: {}", selected_project.name);
     println!("To change to this directory, run: cd {}", selected_project.path);
-}
\ No newline at end of file
+}
+
+fn describe(project: &Project) -> String {
+    let mut line = format!("{} - {}", project.name, project.path);
+    if let Some(status) = git::status(Path::new(&project.path)) {
+        line.push_str(&format!(" ({})", status.summary()));
+    }
+    if let Some(group) = &project.group {
+        line.push_str(&format!(" [{}]", group));
+    }
+    if !project.tags.is_empty() {
+        line.push_str(&format!(" #{}", project.tags.join(" #")));
+    }
+    line
+}
+
+/// Walks through each project whose directory is gone and asks what to do with it.
+fn repair_missing(projects: &mut Vec<Project>) {
+    println!("The following projects have missing directories:");
+    for project in projects.iter().filter(|p| p.is_missing()) {
+        println!("- {}: {}", project.name, project.path);
+    }
+
+    let mut changed = false;
+    let mut i = 0;
+    while i < projects.len() {
+        if !projects[i].is_missing() {
+            i += 1;
+            continue;
+        }
+
+        let project = &projects[i];
+        let mut options = vec!["Keep", "Remove from list", "Relocate (point at a new path)"];
+        if project.remote.is_some() {
+            options.push("Re-clone from recorded remote");
+        }
+        let choice = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("'{}' is missing", project.name))
+            .items(&options)
+            .default(0)
+            .interact()
+            .unwrap();
+
+        match choice {
+            1 => {
+                let removed = projects.remove(i);
+                println!("Removed '{}' from the list.", removed.name);
+                changed = true;
+                continue;
+            }
+            2 => {
+                let new_path: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("New path")
+                    .interact_text()
+                    .unwrap();
+                match Path::new(new_path.trim()).canonicalize() {
+                    Ok(path) if path.is_dir() => {
+                        projects[i].path = path.display().to_string();
+                        println!("'{}' now points at {}.", projects[i].name, projects[i].path);
+                        changed = true;
+                    }
+                    _ => eprintln!("'{}' is not an existing directory. Keeping entry as is.", new_path.trim()),
+                }
+            }
+            3 => match doctor::reclone(project) {
+                Ok(()) => println!("Re-cloned '{}' into {}.", project.name, project.path),
+                Err(e) => eprintln!("{}", e),
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if changed {
+        match registry::save(projects) {
+            Ok(()) => println!("Project list updated."),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
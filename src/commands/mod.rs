@@ -1,10 +1,33 @@
+pub mod branch;
+pub mod changelog;
+pub mod ci;
 pub mod create;
 pub mod config;
+pub mod dash;
+pub mod doctor;
+pub mod foreach;
+pub mod group;
+pub mod issue;
 pub mod list;
+pub mod open;
+pub mod policy;
+pub mod pr;
+pub mod release;
+pub mod remote;
+pub mod status;
+pub mod sync;
+pub mod tag;
 pub mod update;
 pub mod upgrade;
 
-use std::process::Command;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use crate::git;
+use crate::github;
+use crate::registry::Project;
 
 pub fn ensure_gh_installed() {
     let check = Command::new("which").arg("gh").output();
@@ -34,6 +57,130 @@ pub fn ensure_gh_installed() {
     println!("GitHub CLI installed successfully.");
 }
 
+/// `"s"` unless `n` is one, as in "1 file" and "2 files".
+pub fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+/// Runs git in `dir`. Errors carry git's first stderr line. Without `prompt`,
+/// a remote asking for credentials fails instead of stalling on the terminal,
+/// which is what commands working through many projects at once need.
+pub fn run_git(dir: &Path, args: &[&str], prompt: bool) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(dir).stderr(Stdio::piped());
+    if prompt {
+        command.stdin(Stdio::inherit());
+    } else {
+        command.env("GIT_TERMINAL_PROMPT", "0").stdin(Stdio::null());
+    }
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("git failed").trim().to_string())
+}
+
+/// Asks a yes/no question that defaults to no. `yes` answers it up front; without
+/// a terminal to ask on, that is required.
+pub fn confirm(question: &str, yes: bool) -> Result<bool, String> {
+    if yes {
+        return Ok(true);
+    }
+    ask(question, None, "--yes")
+}
+
+/// Like `confirm`, for questions that flags can answer either way: `preset` is
+/// their answer, and `flags` names them in the error when there is no terminal.
+pub fn ask(question: &str, preset: Option<bool>, flags: &str) -> Result<bool, String> {
+    if let Some(answer) = preset {
+        println!("{} {}", question, if answer { "yes" } else { "no" });
+        return Ok(answer);
+    }
+    require_terminal(question, flags)?;
+    Ok(Confirm::with_theme(&ColorfulTheme::default()).with_prompt(question).default(false).interact().unwrap())
+}
+
+/// Errors instead of waiting for input that can never come. `flags` names the
+/// options that make `question` unnecessary.
+pub fn require_terminal(question: &str, flags: &str) -> Result<(), String> {
+    if io::stdin().is_terminal() {
+        return Ok(());
+    }
+    Err(format!("Cannot ask \"{}\": stdin is not a terminal. Pass {}.", question.trim_end_matches('?'), flags))
+}
+
+/// Open pull requests or issues, as listed by `odin pr list` and `odin issue list`.
+pub struct OpenItems {
+    /// The `gh` command that lists them, `pr` or `issue`.
+    pub kind: &'static str,
+    /// What they are called in messages, e.g. `pull requests`.
+    pub name: &'static str,
+    /// The `--json` fields `line` reads.
+    pub fields: &'static str,
+    /// Formats one item of `gh <kind> list --json` output.
+    pub line: fn(&serde_json::Value) -> String,
+}
+
+impl OpenItems {
+    fn fetch(&self, dir: &Path) -> Result<Vec<String>, String> {
+        let json = github::output(dir, &[self.kind, "list", "--state", "open", "--json", self.fields])
+            .ok_or_else(|| format!("could not list {}", self.name))?;
+        let values: Vec<serde_json::Value> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        Ok(values.iter().map(self.line).collect())
+    }
+
+    /// Lists the open items of the repository in `repo`.
+    pub fn list(&self, repo: &Path) -> Result<(), String> {
+        let lines = self.fetch(repo)?;
+        if lines.is_empty() {
+            println!("No open {}.", self.name);
+        }
+        for line in lines {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Lists the open items of every project with a GitHub remote, asking GitHub in parallel.
+    pub fn list_projects(&self, projects: &[Project]) {
+        let projects: Vec<&Project> = projects
+            .iter()
+            .filter(|p| !p.is_missing() && git::remote_url(Path::new(&p.path)).is_some_and(|url| url.contains("github.com")))
+            .collect();
+        if projects.is_empty() {
+            println!("No matching projects with a GitHub remote.");
+            return;
+        }
+
+        let results: Vec<Result<Vec<String>, String>> = thread::scope(|scope| {
+            let handles: Vec<_> = projects
+                .iter()
+                .map(|p| scope.spawn(|| self.fetch(Path::new(&p.path))))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut total = 0;
+        for (project, result) in projects.iter().zip(results) {
+            match result {
+                Ok(lines) if lines.is_empty() => {}
+                Ok(lines) => {
+                    total += lines.len();
+                    println!("{} ({} open)", project.name, lines.len());
+                    for line in lines {
+                        println!("  {}", line);
+                    }
+                }
+                Err(e) => eprintln!("{}: {}", project.name, e),
+            }
+        }
+        if total == 0 {
+            println!("No open {} in {} project{}.", self.name, projects.len(), plural(projects.len()));
+        }
+    }
+}
+
 fn install_gh_linux() -> bool {
     // Assume Debian/Ubuntu for now
     let commands = vec![
@@ -54,11 +201,11 @@ fn install_gh_linux() -> bool {
 }
 
 fn install_gh_macos() -> bool {
-    let status = Command::new("brew").args(&["install", "gh"]).status();
+    let status = Command::new("brew").args(["install", "gh"]).status();
     status.map(|s| s.success()).unwrap_or(false)
 }
 
 fn install_gh_windows() -> bool {
-    let status = Command::new("winget").args(&["install", "--id", "GitHub.cli"]).status();
+    let status = Command::new("winget").args(["install", "--id", "GitHub.cli"]).status();
     status.map(|s| s.success()).unwrap_or(false)
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::registry;
+use crate::settings;
+
+pub enum Target {
+    Editor,
+    Web,
+    Files,
+}
+
+pub fn run(name: Option<&str>, target: Target) {
+    let path = match name {
+        Some(name) => {
+            let mut projects = registry::load();
+            match registry::find_by_name(&mut projects, name) {
+                Some(project) => PathBuf::from(&project.path),
+                None => {
+                    eprintln!("Project '{}' not found in the registry.", name);
+                    return;
+                }
+            }
+        }
+        None => match current_project() {
+            Some(path) => path,
+            None => {
+                eprintln!("Not inside a registered Odin project. Pass a project name.");
+                return;
+            }
+        },
+    };
+
+    if !path.exists() {
+        eprintln!("Project directory {} does not exist. Run 'odin list' to repair it.", path.display());
+        return;
+    }
+
+    let result = match target {
+        Target::Editor => launch_editor(&path),
+        Target::Web => {
+            crate::commands::ensure_gh_installed();
+            // gh prints why it failed (no remote, not logged in) before exiting non-zero
+            match Command::new("gh").arg("browse").current_dir(&path).status() {
+                Ok(status) if !status.success() => Err(io::Error::other("gh browse failed")),
+                status => status.map(|_| ()),
+            }
+        }
+        Target::Files => open_file_manager(&path),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to open {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+}
+
+/// Finds the registered project containing the current directory.
+fn current_project() -> Option<PathBuf> {
+    let projects = registry::load();
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .find_map(|dir| registry::find_by_path(&projects, dir))
+        .map(|project| PathBuf::from(&project.path))
+}
+
+/// Opens the project directory `path` in the editor.
+pub fn launch_editor(path: &Path) -> io::Result<()> {
+    editor_command(path).current_dir(path).status()?;
+    Ok(())
+}
+
+/// Builds the command that opens `path` with the configured editor, falling
+/// back to `$VISUAL`, `$EDITOR` and `vi`.
+pub fn editor_command(path: &Path) -> Command {
+    let set = |value: &String| !value.trim().is_empty();
+    let editor = settings::load()
+        .editor
+        .filter(set)
+        .or_else(|| std::env::var("VISUAL").ok().filter(set))
+        .or_else(|| std::env::var("EDITOR").ok().filter(set))
+        .unwrap_or_else(|| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let mut command = Command::new(parts.next().unwrap_or("vi"));
+    command.args(parts).arg(path);
+    command
+}
+
+fn open_file_manager(path: &Path) -> io::Result<()> {
+    let program = match std::env::consts::OS {
+        "macos" => "open",
+        "windows" => "explorer",
+        _ => "xdg-open",
+    };
+    Command::new(program).arg(path).status()?;
+    Ok(())
+}
@@ -0,0 +1,366 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use clap::Subcommand;
+use serde_json::{json, Value};
+use super::{branch, plural};
+use crate::git;
+use crate::github;
+use crate::policy::{self, Label, Policy, Protection};
+use crate::project;
+use crate::registry::{self, Filter};
+
+#[derive(Subcommand)]
+pub enum PolicyAction {
+    /// Create labels and protect the default branch as the policy file says
+    Apply {
+        /// Apply to every registered project with a GitHub remote
+        #[arg(long)]
+        all: bool,
+        /// Apply to projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Apply to projects in this group
+        #[arg(long)]
+        group: Option<String>,
+        /// Only report where projects differ from the policy; exits 1 if any do
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+pub fn run(action: &PolicyAction) {
+    let PolicyAction::Apply { all, tag, group, check } = action;
+    let filter = Filter::new(tag.clone(), group.clone());
+    let targets: Vec<(String, PathBuf)> = if *all || !filter.is_empty() {
+        filter
+            .apply(registry::load())
+            .into_iter()
+            .filter(|p| !p.is_missing() && git::remote_url(Path::new(&p.path)).is_some_and(|url| url.contains("github.com")))
+            .map(|p| (p.name, PathBuf::from(p.path)))
+            .collect()
+    } else {
+        current_project().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        })
+    };
+    if targets.is_empty() {
+        println!("No matching projects with a GitHub remote.");
+        return;
+    }
+    crate::commands::ensure_gh_installed();
+
+    let mut differing = 0;
+    let mut failed = 0;
+    for (name, dir) in &targets {
+        match enforce(dir, *check) {
+            Ok(Outcome::NoPolicy) => println!(
+                "{}: no policy; add POLICY.toml to the project or create {}",
+                name,
+                policy::global_file().display()
+            ),
+            Ok(Outcome::Matches) => println!("{}: matches the policy", name),
+            Ok(Outcome::Differs(lines)) => {
+                differing += 1;
+                println!("{}: {} difference{} from the policy", name, lines.len(), plural(lines.len()));
+                for line in lines {
+                    println!("  {}", line);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{}: {}", name, e);
+            }
+        }
+    }
+
+    if *check {
+        if targets.len() > 1 {
+            println!("{} of {} projects differ from the policy.", differing, targets.len());
+        }
+        if differing > 0 {
+            std::process::exit(1);
+        }
+    } else if differing > 0 && failed == 0 {
+        println!("Applied the policy to {} project{}.", differing, plural(differing));
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn current_project() -> Result<Vec<(String, PathBuf)>, String> {
+    let repo = Path::new(".");
+    let Some(root) = git::output(repo, &["rev-parse", "--show-toplevel"]) else {
+        return Err("Not in a Git repository.".to_string());
+    };
+    if git::remote_url(repo).is_none() {
+        return Err("This project has no remote. Push it to GitHub first with 'odin update'.".to_string());
+    }
+    let root = PathBuf::from(root);
+    let name = project::load(&root)
+        .app
+        .name
+        .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    Ok(vec![(name, root)])
+}
+
+enum Outcome {
+    NoPolicy,
+    Matches,
+    /// What differed; in apply mode it has been fixed.
+    Differs(Vec<String>),
+}
+
+/// One request that brings the repository in line, and what it fixes.
+struct Fix {
+    lines: Vec<String>,
+    method: &'static str,
+    path: String,
+    body: Value,
+}
+
+fn enforce(dir: &Path, check: bool) -> Result<Outcome, String> {
+    let Some((policy, _)) = policy::load(dir)? else {
+        return Ok(Outcome::NoPolicy);
+    };
+    let fixes = fixes(dir, &policy)?;
+    if fixes.is_empty() {
+        return Ok(Outcome::Matches);
+    }
+    if !check {
+        for fix in &fixes {
+            api_write(dir, fix).map_err(|e| format!("failed to apply '{}': {}", fix.lines[0], e))?;
+        }
+    }
+    Ok(Outcome::Differs(fixes.into_iter().flat_map(|f| f.lines).collect()))
+}
+
+fn fixes(dir: &Path, policy: &Policy) -> Result<Vec<Fix>, String> {
+    let mut fixes = Vec::new();
+    if !policy.labels.is_empty() {
+        let json = github::output(dir, &["api", "repos/{owner}/{repo}/labels", "--paginate", "--jq", ".[]"])
+            .ok_or_else(|| "could not read the repository's labels".to_string())?;
+        let existing: Vec<Value> = json.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+        fixes.extend(policy.labels.iter().filter_map(|label| label_fix(label, &existing)));
+    }
+    if let Some(protection) = &policy.protection {
+        let branch = branch::default_branch(dir)?;
+        let current = current_protection(dir, &branch)?;
+        fixes.extend(protection_fix(&branch, protection, current.as_ref()));
+    }
+    Ok(fixes)
+}
+
+/// The protection of `branch`, or `None` if GitHub says it isn't protected.
+/// Any other failure is an error, so a protection that couldn't be read is never overwritten.
+fn current_protection(dir: &Path, branch: &str) -> Result<Option<Value>, String> {
+    let output = Command::new("gh")
+        .args(["api", &format!("repos/{{owner}}/{{repo}}/branches/{}/protection", encode(branch))])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Error running gh command: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        return serde_json::from_str(&stdout)
+            .map(Some)
+            .map_err(|e| format!("unexpected branch protection from GitHub: {}", e));
+    }
+    if stderr.contains("HTTP 404") && (stderr.contains("Branch not protected") || stdout.contains("Branch not protected")) {
+        return Ok(None);
+    }
+    Err(format!("could not read the protection of '{}': {}", branch, stderr.trim()))
+}
+
+fn label_fix(label: &Label, existing: &[Value]) -> Option<Fix> {
+    let color = label.color.trim_start_matches('#').to_lowercase();
+    let body = json!({ "name": label.name, "color": color, "description": label.description });
+    // GitHub matches label names case-insensitively
+    let Some(current) = existing
+        .iter()
+        .find(|l| l["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(&label.name)))
+    else {
+        return Some(Fix {
+            lines: vec![format!("label '{}' is missing", label.name)],
+            method: "POST",
+            path: "repos/{owner}/{repo}/labels".to_string(),
+            body,
+        });
+    };
+
+    let name = current["name"].as_str().unwrap_or_default();
+    let mut changes = Vec::new();
+    if name != label.name {
+        changes.push(format!("name '{}' -> '{}'", name, label.name));
+    }
+    let current_color = current["color"].as_str().unwrap_or_default().to_lowercase();
+    if current_color != color {
+        changes.push(format!("color {} -> {}", current_color, color));
+    }
+    let description = current["description"].as_str().unwrap_or_default();
+    if description != label.description {
+        changes.push(format!("description \"{}\" -> \"{}\"", description, label.description));
+    }
+    if changes.is_empty() {
+        return None;
+    }
+    Some(Fix {
+        lines: vec![format!("label '{}': {}", label.name, changes.join(", "))],
+        method: "PATCH",
+        path: format!("repos/{{owner}}/{{repo}}/labels/{}", encode(name)),
+        body: json!({ "new_name": label.name, "color": color, "description": label.description }),
+    })
+}
+
+/// Compares the wanted protection of `branch` with `current` (`None` if it isn't
+/// protected). The request replaces the whole protection, so every setting the
+/// policy leaves out is copied from `current`.
+fn protection_fix(branch: &str, wanted: &Protection, current: Option<&Value>) -> Option<Fix> {
+    let current = current.cloned().unwrap_or(Value::Null);
+    let reviews = &current["required_pull_request_reviews"];
+    let checks = &current["required_status_checks"];
+    let have_reviews = reviews["required_approving_review_count"].as_u64().unwrap_or(0);
+    let have_checks: Vec<String> = checks["contexts"]
+        .as_array()
+        .map(|c| c.iter().filter_map(|c| c.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    let have_strict = checks["strict"].as_bool().unwrap_or(false);
+    let enabled = |key: &str| current[key]["enabled"].as_bool().unwrap_or(false);
+
+    let mut lines = Vec::new();
+    if current.is_null() {
+        lines.push(format!("branch '{}' is not protected", branch));
+    }
+    let reviews_count = wanted.required_reviews.unwrap_or(have_reviews);
+    if reviews_count != have_reviews {
+        lines.push(format!("{}: required reviews {} -> {}", branch, have_reviews, reviews_count));
+    }
+    let required_checks = wanted.required_checks.clone().unwrap_or_else(|| have_checks.clone());
+    let (mut sorted_wanted, mut sorted_have) = (required_checks.clone(), have_checks.clone());
+    sorted_wanted.sort();
+    sorted_have.sort();
+    if sorted_wanted != sorted_have {
+        let shown = |c: &[String]| if c.is_empty() { "(none)".to_string() } else { c.join(", ") };
+        lines.push(format!("{}: required checks {} -> {}", branch, shown(&have_checks), shown(&required_checks)));
+    }
+    let mut flag = |label: &str, wanted: Option<bool>, have: bool| {
+        let value = wanted.unwrap_or(have);
+        if value != have {
+            lines.push(format!("{}: {} {} -> {}", branch, label, have, value));
+        }
+        value
+    };
+    let strict = flag("up to date before merging", wanted.strict, have_strict);
+    let enforce_admins = flag("enforce for admins", wanted.enforce_admins, enabled("enforce_admins"));
+    let force_pushes = flag("allow force pushes", wanted.allow_force_pushes, enabled("allow_force_pushes"));
+    let deletions = flag("allow deletions", wanted.allow_deletions, enabled("allow_deletions"));
+    if lines.is_empty() {
+        return None;
+    }
+
+    let required_status_checks = if required_checks.is_empty() && !strict {
+        Value::Null
+    } else {
+        // `checks` keeps the app each check is pinned to; `contexts` would drop it
+        let pinned = checks["checks"].as_array().cloned().unwrap_or_default();
+        let checks: Vec<Value> = required_checks
+            .iter()
+            .map(|name| {
+                pinned
+                    .iter()
+                    .find(|c| c["context"].as_str() == Some(name.as_str()))
+                    .map(|c| json!({ "context": name, "app_id": c["app_id"] }))
+                    .unwrap_or_else(|| json!({ "context": name }))
+            })
+            .collect();
+        json!({ "strict": strict, "checks": checks })
+    };
+    // A pull request rule can require no approvals; it is only left off if there is none to keep
+    let required_pull_request_reviews = if reviews.is_null() && reviews_count == 0 {
+        Value::Null
+    } else {
+        let mut body = json!({
+            "required_approving_review_count": reviews_count,
+            "dismiss_stale_reviews": reviews["dismiss_stale_reviews"].as_bool().unwrap_or(false),
+            "require_code_owner_reviews": reviews["require_code_owner_reviews"].as_bool().unwrap_or(false),
+            "require_last_push_approval": reviews["require_last_push_approval"].as_bool().unwrap_or(false),
+        });
+        // Only organization repositories have these; sending them elsewhere is an error
+        for key in ["dismissal_restrictions", "bypass_pull_request_allowances"] {
+            if !reviews[key].is_null() {
+                body[key] = actors(&reviews[key]);
+            }
+        }
+        body
+    };
+    let mut body = json!({
+        "required_status_checks": required_status_checks,
+        "enforce_admins": enforce_admins,
+        "required_pull_request_reviews": required_pull_request_reviews,
+        "restrictions": if current["restrictions"].is_null() { Value::Null } else { actors(&current["restrictions"]) },
+        "allow_force_pushes": force_pushes,
+        "allow_deletions": deletions,
+    });
+    for key in [
+        "required_linear_history",
+        "required_conversation_resolution",
+        "block_creations",
+        "lock_branch",
+        "allow_fork_syncing",
+    ] {
+        body[key] = Value::Bool(enabled(key));
+    }
+    Some(Fix {
+        lines,
+        method: "PUT",
+        path: format!("repos/{{owner}}/{{repo}}/branches/{}/protection", encode(branch)),
+        body,
+    })
+}
+
+/// Turns the users, teams and apps of a protection setting, as GitHub reports
+/// them, into the logins and slugs it expects back.
+fn actors(value: &Value) -> Value {
+    let names = |key: &str, field: &str| -> Vec<Value> {
+        value[key]
+            .as_array()
+            .map(|items| items.iter().map(|i| i[field].clone()).collect())
+            .unwrap_or_default()
+    };
+    json!({ "users": names("users", "login"), "teams": names("teams", "slug"), "apps": names("apps", "slug") })
+}
+
+/// Sends `fix` with `gh api`, passing the body on stdin.
+fn api_write(dir: &Path, fix: &Fix) -> Result<(), String> {
+    let mut child = Command::new("gh")
+        .args(["api", "-X", fix.method, &fix.path, "--input", "-"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Error running gh command: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(fix.body.to_string().as_bytes());
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Percent-encodes a label or branch name for use in an API path.
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,210 @@
+use std::path::Path;
+use std::process::Command;
+use clap::Subcommand;
+use super::{branch, run_git, OpenItems};
+use crate::git;
+use crate::github;
+use crate::project::{self, MergeStrategy};
+use crate::registry::{self, Filter};
+
+#[derive(Subcommand)]
+pub enum PrAction {
+    /// Push the current branch and open a pull request, prefilled from its commits
+    Create {
+        /// Branch to merge into (defaults to the default branch)
+        #[arg(long)]
+        base: Option<String>,
+        /// Open the pull request as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+    /// List open pull requests in the current project or across the registry
+    List {
+        /// List pull requests in every registered project
+        #[arg(long)]
+        all: bool,
+        /// List pull requests in projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// List pull requests in projects in this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Check out a pull request's branch
+    Checkout {
+        /// Pull request number
+        number: u32,
+    },
+    /// Merge a pull request with the project's merge strategy
+    #[command(group(clap::ArgGroup::new("strategy").args(["merge", "squash", "rebase"])))]
+    Merge {
+        /// Pull request number (defaults to the one for the current branch)
+        number: Option<u32>,
+        /// Create a merge commit
+        #[arg(long)]
+        merge: bool,
+        /// Squash the commits into one
+        #[arg(long)]
+        squash: bool,
+        /// Rebase the commits onto the base branch
+        #[arg(long)]
+        rebase: bool,
+        /// Keep the branch after merging
+        #[arg(long)]
+        keep_branch: bool,
+    },
+}
+
+pub fn run(action: &PrAction) {
+    crate::commands::ensure_gh_installed();
+    let repo = Path::new(".");
+
+    let result = match action {
+        PrAction::Create { base, draft } => {
+            in_repo(repo).and_then(|branch_name| {
+                let base = match base {
+                    Some(base) => base.clone(),
+                    None => branch::default_branch(repo)?,
+                };
+                create(repo, &branch_name, &base, *draft)
+            })
+        }
+        PrAction::List { all, tag, group } => {
+            let filter = Filter::new(tag.clone(), group.clone());
+            if *all || !filter.is_empty() {
+                OPEN_PRS.list_projects(&filter.apply(registry::load()));
+                Ok(())
+            } else {
+                OPEN_PRS.list(repo)
+            }
+        }
+        PrAction::Checkout { number } => gh(&["pr", "checkout", &number.to_string()], "check out the pull request"),
+        PrAction::Merge { number, merge, squash, rebase, keep_branch } => {
+            let strategy = if *merge {
+                MergeStrategy::Merge
+            } else if *squash {
+                MergeStrategy::Squash
+            } else if *rebase {
+                MergeStrategy::Rebase
+            } else {
+                project::load(repo).pr.merge.unwrap_or(MergeStrategy::Merge)
+            };
+            merge_pr(*number, strategy, *keep_branch)
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Returns the current branch, or an error if this isn't a repository on a branch.
+fn in_repo(repo: &Path) -> Result<String, String> {
+    if git::remote_url(repo).is_none() {
+        return Err("This project has no remote. Push it to GitHub first with 'odin update'.".to_string());
+    }
+    git::current_branch(repo).ok_or_else(|| "Not on a branch (detached HEAD).".to_string())
+}
+
+/// Pushes `branch` and opens a pull request into `base`, titled and described
+/// from the commits between them. Does nothing if one is already open.
+pub fn create(repo: &Path, branch: &str, base: &str, draft: bool) -> Result<(), String> {
+    if branch == base {
+        return Err(format!("'{}' is the base branch; create a branch first with 'odin branch new <name>'.", branch));
+    }
+    let existing = github::output(repo, &["pr", "view", branch, "--json", "state,url", "-q", ".state + \" \" + .url"]);
+    if let Some(("OPEN", url)) = existing.as_deref().and_then(|s| s.split_once(' ')) {
+        println!("A pull request is already open: {}", url);
+        return Ok(());
+    }
+
+    // Work out the title first, so a branch with nothing to propose isn't published
+    let remote_base = format!("origin/{}", base);
+    let base_ref = if git::ref_exists(repo, &remote_base) { remote_base.as_str() } else { base };
+    let (title, body) = prefill(repo, branch, base_ref)
+        .ok_or_else(|| format!("'{}' has no commits that aren't already on '{}'.", branch, base))?;
+
+    println!("Pushing '{}'...", branch);
+    run_git(repo, &["push", "-q", "-u", "origin", branch], true).map_err(|e| format!("Failed to push '{}': {}", branch, e))?;
+
+    println!("Opening a pull request into '{}': {}", base, title);
+    let mut args = vec!["pr", "create", "--base", base, "--head", branch, "--title", &title, "--body", &body];
+    if draft {
+        args.push("--draft");
+    }
+    gh(&args, "open the pull request")
+}
+
+/// Title and body for a pull request: the commit itself if there is only one,
+/// otherwise the branch name and a list of the commit subjects.
+fn prefill(repo: &Path, branch: &str, base_ref: &str) -> Option<(String, String)> {
+    let log = git::output(repo, &["log", "--reverse", "--format=%s%x1f%b%x1e", &format!("{}..HEAD", base_ref)])?;
+    let commits: Vec<(&str, &str)> = log
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.split_once('\x1f').unwrap_or((entry, "")))
+        .collect();
+    match commits.as_slice() {
+        [] => None,
+        [(subject, body)] => Some((subject.to_string(), body.trim().to_string())),
+        many => {
+            let body = many.iter().map(|(subject, _)| format!("- {}", subject)).collect::<Vec<_>>().join("\n");
+            Some((title_from_branch(branch), body))
+        }
+    }
+}
+
+/// `feature/user-login` becomes `User login`.
+fn title_from_branch(branch: &str) -> String {
+    let name = branch.rsplit('/').next().unwrap_or(branch).replace(['-', '_'], " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => branch.to_string(),
+    }
+}
+
+const OPEN_PRS: OpenItems = OpenItems {
+    kind: "pr",
+    name: "pull requests",
+    fields: "number,title,headRefName,author,isDraft",
+    line: |pr| {
+        let draft = if pr["isDraft"].as_bool().unwrap_or(false) { " [draft]" } else { "" };
+        format!(
+            "#{:<5} {}{} ({}, @{})",
+            pr["number"].as_u64().unwrap_or(0),
+            pr["title"].as_str().unwrap_or_default(),
+            draft,
+            pr["headRefName"].as_str().unwrap_or_default(),
+            pr["author"]["login"].as_str().unwrap_or_default()
+        )
+    },
+};
+
+fn merge_pr(number: Option<u32>, strategy: MergeStrategy, keep_branch: bool) -> Result<(), String> {
+    let flag = match strategy {
+        MergeStrategy::Merge => "--merge",
+        MergeStrategy::Squash => "--squash",
+        MergeStrategy::Rebase => "--rebase",
+    };
+    let number = number.map(|n| n.to_string());
+    let mut args = vec!["pr", "merge"];
+    args.extend(number.as_deref());
+    args.push(flag);
+    if !keep_branch {
+        args.push("--delete-branch");
+    }
+    println!("Merging with {}...", flag.trim_start_matches("--"));
+    gh(&args, "merge the pull request")
+}
+
+/// Runs `gh` with the terminal attached; `what` completes "Failed to ...".
+fn gh(args: &[&str], what: &str) -> Result<(), String> {
+    let status = Command::new("gh").args(args).status().map_err(|e| format!("Error running gh command: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to {}.", what))
+    }
+}
@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use regex::Regex;
+use super::{changelog, confirm, plural};
+use crate::git;
+
+pub struct Options {
+    /// `major`, `minor`, `patch` or an explicit version such as `2.0.0`.
+    pub bump: String,
+    pub draft: bool,
+    pub assets: Vec<String>,
+    pub yes: bool,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    /// Pre-release part after `-`, e.g. `rc.1`.
+    pre: Option<String>,
+}
+
+impl Version {
+    fn parse(value: &str) -> Option<Version> {
+        let value = value.trim().trim_start_matches('v');
+        let (core, pre) = match value.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (value, None),
+        };
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => Some(Version { major, minor, patch, pre }),
+            _ => None,
+        }
+    }
+
+    fn bump(&self, part: &str) -> Option<Version> {
+        let (major, minor, patch) = (self.major, self.minor, self.patch);
+        Some(match part {
+            "major" => Version { major: major + 1, minor: 0, patch: 0, pre: None },
+            "minor" => Version { major, minor: minor + 1, patch: 0, pre: None },
+            // A pre-release is released as its own version: 1.2.0-rc.1 becomes 1.2.0
+            "patch" if self.pre.is_some() => Version { pre: None, ..self.clone() },
+            "patch" => Version { major, minor, patch: patch + 1, pre: None },
+            explicit => Version::parse(explicit)?,
+        })
+    }
+}
+
+impl Ord for Version {
+    /// Semantic version order: a pre-release comes before its release.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.split('.').map(Identifier::new).cmp(b.split('.').map(Identifier::new)),
+            })
+    }
+}
+
+/// One dot-separated part of a pre-release, ordered as semver specifies:
+/// numbers by value and before any alphanumeric part, which compare as text.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Identifier<'a> {
+    Numeric(u64),
+    Alphanumeric(&'a str),
+}
+
+impl<'a> Identifier<'a> {
+    fn new(part: &'a str) -> Identifier<'a> {
+        part.parse().map_or(Identifier::Alphanumeric(part), Identifier::Numeric)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// A file that records the project version.
+struct Manifest {
+    path: PathBuf,
+    version: Version,
+}
+
+/// TOML manifests and the sections that hold their `version` key.
+const TOML_MANIFESTS: &[(&str, &[&str])] = &[
+    ("Cargo.toml", &["package", "workspace.package"]),
+    ("pyproject.toml", &["project", "tool.poetry"]),
+];
+
+pub fn run(options: &Options) {
+    if let Err(e) = release(Path::new("."), options) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn release(repo: &Path, options: &Options) -> Result<(), String> {
+    if git::output(repo, &["rev-parse", "--git-dir"]).is_none() {
+        return Err("Not in a Git repository.".to_string());
+    }
+    if git::status(repo).is_some_and(|s| s.staged + s.unstaged + s.conflicted > 0) {
+        return Err("You have uncommitted changes. Commit them with 'odin update' before releasing.".to_string());
+    }
+    for asset in &options.assets {
+        if !Path::new(asset).is_file() {
+            return Err(format!("Asset '{}' does not exist.", asset));
+        }
+    }
+
+    let previous_tag = changelog::previous_tag(repo);
+    let manifests = find_manifests(repo);
+    let current = match (manifests.first(), &previous_tag) {
+        (Some(manifest), _) => manifest.version.clone(),
+        (None, Some(tag)) => Version::parse(tag).ok_or_else(|| format!("Cannot read a version from tag '{}'.", tag))?,
+        (None, None) => Version { major: 0, minor: 0, patch: 0, pre: None },
+    };
+    let next = current
+        .bump(&options.bump)
+        .ok_or_else(|| format!("'{}' is not major, minor, patch or a version like 1.2.3.", options.bump))?;
+    if next <= current {
+        return Err(format!("{} is not newer than the current version {}.", next, current));
+    }
+    let tag = format!("v{}", next);
+    if git::ref_exists(repo, &format!("refs/tags/{}", tag)) {
+        return Err(format!("Tag {} already exists.", tag));
+    }
+
+    let range = previous_tag.as_ref().map_or("HEAD".to_string(), |t| format!("{}..HEAD", t));
+    let commits = git::count(repo, &range).unwrap_or(0);
+    let mut files: Vec<String> = manifests.iter().map(|m| m.path.display().to_string()).collect();
+    // A project that keeps a changelog gets this release's section added to it
+    let keeps_changelog = repo.join("CHANGELOG.md").is_file();
+    if keeps_changelog {
+        files.push("CHANGELOG.md".to_string());
+    }
+    println!("Current version: {}", current);
+    println!("New version:     {}", next);
+    if !files.is_empty() {
+        println!("Files:           {}", files.join(", "));
+    }
+    println!(
+        "Changes:         {} commit{} since {}",
+        commits,
+        plural(commits),
+        previous_tag.as_deref().unwrap_or("the first commit")
+    );
+    if !confirm(&format!("Release {}?", tag), options.yes)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    // Notes are taken before the release commit so it doesn't list itself
+    let notes = changelog::notes(repo, previous_tag.as_deref());
+
+    for manifest in &manifests {
+        set_version(&manifest.path, &manifest.version, &next)?;
+    }
+    if keeps_changelog {
+        changelog::add_release(repo, &tag, &notes)?;
+    }
+    let mut changed = files.clone();
+    if update_cargo_lock(repo, &current, &next) {
+        changed.push("Cargo.lock".to_string());
+    }
+    if !changed.is_empty() {
+        let mut add = vec!["add", "--"];
+        add.extend(changed.iter().map(String::as_str));
+        if git::output(repo, &add).is_none() || git::output(repo, &["commit", "-q", "-m", &format!("Release {}", tag)]).is_none() {
+            return Err("Failed to commit the version bump.".to_string());
+        }
+        println!("Committed {}.", changed.join(", "));
+    }
+    if git::output(repo, &["tag", "-a", &tag, "-m", &format!("Release {}", tag)]).is_none() {
+        return Err(format!("Failed to create tag {}.", tag));
+    }
+    println!("Tagged {}.", tag);
+
+    if git::remote_url(repo).is_none() {
+        println!("No remote configured; the release was only tagged locally.");
+        return Ok(());
+    }
+    println!("Pushing...");
+    let pushed = Command::new("git").args(["push", "-q", "origin", "HEAD", &tag]).status();
+    if !pushed.is_ok_and(|s| s.success()) {
+        return Err(format!("Failed to push. Push the branch and tag with 'git push origin HEAD {}'.", tag));
+    }
+
+    crate::commands::ensure_gh_installed();
+    let mut args = vec!["release", "create", tag.as_str(), "--title", tag.as_str(), "--notes", notes.as_str()];
+    if options.draft {
+        args.push("--draft");
+    }
+    args.extend(options.assets.iter().map(String::as_str));
+    let created = Command::new("gh").args(&args).status();
+    if !created.is_ok_and(|s| s.success()) {
+        return Err(format!("Tagged and pushed {}, but creating the GitHub release failed. Retry with 'gh release create {}'.", tag, tag));
+    }
+    println!("Released {}{}.", tag, if options.draft { " as a draft" } else { "" });
+    Ok(())
+}
+
+fn find_manifests(repo: &Path) -> Vec<Manifest> {
+    let mut manifests = Vec::new();
+    for (file, sections) in TOML_MANIFESTS {
+        let path = repo.join(file);
+        if let Ok(content) = fs::read_to_string(&path)
+            && let Some(version) = toml_version(&content, sections).and_then(|(_, v)| Version::parse(&v))
+        {
+            manifests.push(Manifest { path: PathBuf::from(file), version });
+        }
+    }
+    if let Ok(content) = fs::read_to_string(repo.join("package.json"))
+        && let Ok(json) = serde_json::from_str::<serde_json::Value>(&content)
+        && let Some(version) = json["version"].as_str().and_then(Version::parse)
+    {
+        manifests.push(Manifest { path: PathBuf::from("package.json"), version });
+    }
+    manifests
+}
+
+/// Finds the `version = "..."` line in one of `sections`; returns its line index and value.
+fn toml_version(content: &str, sections: &[&str]) -> Option<(usize, String)> {
+    let line_re = Regex::new(r#"^\s*version\s*=\s*"([^"]*)""#).unwrap();
+    let mut section = String::new();
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+        } else if sections.contains(&section.as_str())
+            && let Some(caps) = line_re.captures(line)
+        {
+            return Some((i, caps[1].to_string()));
+        }
+    }
+    None
+}
+
+/// Rewrites the version in place, leaving the rest of the file's formatting alone.
+fn set_version(path: &Path, old: &Version, new: &Version) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let updated = if path.extension().is_some_and(|e| e == "json") {
+        let re = Regex::new(&format!(r#""version"(\s*):(\s*)"{}""#, regex::escape(&old.to_string()))).unwrap();
+        re.replacen(&content, 1, format!(r#""version"${{1}}:${{2}}"{}""#, new)).to_string()
+    } else {
+        let file = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let sections = TOML_MANIFESTS.iter().find(|(name, _)| *name == file).map_or(&[][..], |(_, s)| *s);
+        let Some((index, value)) = toml_version(&content, sections) else {
+            return Err(format!("No version found in {}.", path.display()));
+        };
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        lines[index] = lines[index].replacen(&format!("\"{}\"", value), &format!("\"{}\"", new), 1);
+        let mut joined = lines.join("\n");
+        if content.ends_with('\n') {
+            joined.push('\n');
+        }
+        joined
+    };
+    fs::write(path, updated).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Updates the crate's own entry in Cargo.lock. Returns true if it changed.
+fn update_cargo_lock(repo: &Path, old: &Version, new: &Version) -> bool {
+    let Ok(manifest) = fs::read_to_string(repo.join("Cargo.toml")) else {
+        return false;
+    };
+    let Some(name) = manifest.parse::<toml::Table>().ok().and_then(|t| t.get("package")?.get("name")?.as_str().map(str::to_string)) else {
+        return false;
+    };
+    let path = repo.join("Cargo.lock");
+    let Ok(lock) = fs::read_to_string(&path) else {
+        return false;
+    };
+    let entry = format!("name = \"{}\"\nversion = \"{}\"\n", name, old);
+    if !lock.contains(&entry) {
+        return false;
+    }
+    let updated = lock.replacen(&entry, &format!("name = \"{}\"\nversion = \"{}\"\n", name, new), 1);
+    fs::write(&path, updated).is_ok()
+}
@@ -0,0 +1,169 @@
+use std::path::Path;
+use std::process::Command;
+use clap::Subcommand;
+use super::{confirm, plural};
+use crate::git;
+use crate::github;
+use crate::project::{self, GithubConfig};
+
+#[derive(Subcommand)]
+pub enum RemoteAction {
+    /// Apply the [github] section of CONFIG.toml to the GitHub repository
+    Sync {
+        /// Show what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Don't ask before changing the repository's visibility
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+pub fn run(action: &RemoteAction) {
+    let repo = Path::new(".");
+    let result = match action {
+        RemoteAction::Sync { dry_run, yes } => sync(repo, *dry_run, *yes),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// One setting that differs: how to show it and the `gh repo edit` arguments that fix it.
+struct Change {
+    line: String,
+    args: Vec<String>,
+}
+
+fn sync(repo: &Path, dry_run: bool, yes: bool) -> Result<(), String> {
+    if git::output(repo, &["rev-parse", "--git-dir"]).is_none() {
+        return Err("Not in a Git repository.".to_string());
+    }
+    if git::remote_url(repo).is_none() {
+        return Err("This project has no remote. Push it to GitHub first with 'odin update'.".to_string());
+    }
+    let config = project::load(repo).github;
+    if config.description.is_none()
+        && config.homepage.is_none()
+        && config.topics.is_none()
+        && config.visibility.is_none()
+        && config.issues.is_none()
+        && config.wiki.is_none()
+    {
+        return Err("CONFIG.toml has no [github] settings to apply. Add some, e.g.:\n\n[github]\ndescription = \"What this project does\"\ntopics = [\"cli\", \"rust\"]".to_string());
+    }
+    crate::commands::ensure_gh_installed();
+
+    let json = github::output(
+        repo,
+        &["repo", "view", "--json", "nameWithOwner,description,homepageUrl,repositoryTopics,visibility,hasIssuesEnabled,hasWikiEnabled"],
+    )
+    .ok_or_else(|| "Could not read the repository settings from GitHub.".to_string())?;
+    let current: serde_json::Value = serde_json::from_str(&json).map_err(|e| format!("Unexpected output from gh: {}", e))?;
+    let changes = diff(&config, &current);
+
+    let name = current["nameWithOwner"].as_str().unwrap_or("the repository");
+    if changes.is_empty() {
+        println!("{} already matches CONFIG.toml.", name);
+        return Ok(());
+    }
+    println!("Changes to {}:", name);
+    for change in &changes {
+        println!("  {}", change.line);
+    }
+    if dry_run {
+        println!("Dry run: nothing was changed.");
+        return Ok(());
+    }
+    if let Some(visibility) = config.visibility
+        && changes.iter().any(|c| c.args.iter().any(|a| a == "--visibility"))
+        && !confirm(&format!("Make {} {}?", name, visibility.as_str()), yes)?
+    {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let mut args = vec!["repo".to_string(), "edit".to_string()];
+    args.extend(changes.iter().flat_map(|c| c.args.iter().cloned()));
+    let status = Command::new("gh").args(&args).status();
+    if !status.is_ok_and(|s| s.success()) {
+        return Err("Failed to update the repository settings.".to_string());
+    }
+    println!("Updated {} setting{}.", changes.len(), plural(changes.len()));
+    Ok(())
+}
+
+/// Compares the wanted settings with `gh repo view --json` output.
+fn diff(config: &GithubConfig, current: &serde_json::Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let text = |key: &str| current[key].as_str().unwrap_or_default().to_string();
+    let shown = |value: &str| if value.is_empty() { "(none)".to_string() } else { format!("\"{}\"", value) };
+
+    for (label, flag, wanted, have) in [
+        ("description", "--description", &config.description, text("description")),
+        ("homepage", "--homepage", &config.homepage, text("homepageUrl")),
+    ] {
+        if let Some(wanted) = wanted
+            && *wanted != have
+        {
+            changes.push(Change {
+                line: format!("{}: {} -> {}", label, shown(&have), shown(wanted)),
+                args: vec![flag.to_string(), wanted.clone()],
+            });
+        }
+    }
+
+    if let Some(topics) = &config.topics {
+        // GitHub stores topics in lowercase
+        let wanted: Vec<String> = topics.iter().map(|t| t.to_lowercase()).collect();
+        let have: Vec<String> = current["repositoryTopics"]
+            .as_array()
+            .map(|topics| topics.iter().filter_map(|t| t["name"].as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let added: Vec<&String> = wanted.iter().filter(|t| !have.contains(t)).collect();
+        let removed: Vec<&String> = have.iter().filter(|t| !wanted.contains(t)).collect();
+        if !added.is_empty() || !removed.is_empty() {
+            let shown: Vec<String> = added.iter().map(|t| format!("+{}", t)).chain(removed.iter().map(|t| format!("-{}", t))).collect();
+            let mut args = Vec::new();
+            if !added.is_empty() {
+                args.extend(["--add-topic".to_string(), added.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(",")]);
+            }
+            if !removed.is_empty() {
+                args.extend(["--remove-topic".to_string(), removed.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(",")]);
+            }
+            changes.push(Change { line: format!("topics: {}", shown.join(" ")), args });
+        }
+    }
+
+    if let Some(visibility) = config.visibility {
+        let have = text("visibility").to_lowercase();
+        if have != visibility.as_str() {
+            changes.push(Change {
+                line: format!("visibility: {} -> {}", have, visibility.as_str()),
+                args: vec![
+                    "--visibility".to_string(),
+                    visibility.as_str().to_string(),
+                    "--accept-visibility-change-consequences".to_string(),
+                ],
+            });
+        }
+    }
+
+    let state = |enabled: bool| if enabled { "enabled" } else { "disabled" };
+    for (label, flag, wanted, key) in [
+        ("issues", "--enable-issues", config.issues, "hasIssuesEnabled"),
+        ("wiki", "--enable-wiki", config.wiki, "hasWikiEnabled"),
+    ] {
+        let have = current[key].as_bool().unwrap_or(false);
+        if let Some(wanted) = wanted
+            && wanted != have
+        {
+            changes.push(Change {
+                line: format!("{}: {} -> {}", label, state(have), state(wanted)),
+                args: vec![format!("{}={}", flag, wanted)],
+            });
+        }
+    }
+    changes
+}
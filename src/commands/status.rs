@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::git;
+use crate::github;
+use crate::project;
+use crate::registry::{self, Filter, Project};
+
+/// Everything `odin status` reports about one project. Nothing here is fetched
+/// or changed, so ahead/behind counts are as of the last fetch.
+#[derive(Serialize, Default)]
+struct Report {
+    path: String,
+    exists: bool,
+    /// `[app] name` from CONFIG.toml.
+    name: Option<String>,
+    has_config: bool,
+    registered: bool,
+    registry_name: Option<String>,
+    group: Option<String>,
+    tags: Vec<String>,
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: usize,
+    behind: usize,
+    staged: usize,
+    unstaged: usize,
+    untracked: usize,
+    conflicted: usize,
+    last_commit: Option<LastCommit>,
+    remote: Option<String>,
+    /// `public`, `private` or `internal`, if GitHub could be asked.
+    visibility: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LastCommit {
+    hash: String,
+    subject: String,
+    author: String,
+    date: String,
+}
+
+/// Shows the current project, or every project matching `filter` if it is set.
+pub fn run(filter: &Filter, json: bool) {
+    let projects = registry::load();
+    let reports: Vec<Report> = if filter.is_empty() {
+        let Some(root) = std::env::current_dir().ok().and_then(|cwd| git::output(&cwd, &["rev-parse", "--show-toplevel"])) else {
+            eprintln!("Not in a Git repository. Run 'odin status' inside a project, or pass --tag or --group.");
+            std::process::exit(1);
+        };
+        let root = PathBuf::from(root);
+        vec![report(&root, registry::find_by_path(&projects, &root))]
+    } else {
+        let matching = filter.apply(projects);
+        if matching.is_empty() {
+            println!("No matching projects.");
+            return;
+        }
+        matching.iter().map(|p| report(Path::new(&p.path), Some(p))).collect()
+    };
+
+    if json {
+        let value = if filter.is_empty() {
+            serde_json::to_string_pretty(&reports[0])
+        } else {
+            serde_json::to_string_pretty(&reports)
+        };
+        println!("{}", value.unwrap());
+        return;
+    }
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_report(report);
+    }
+}
+
+fn report(dir: &Path, entry: Option<&Project>) -> Report {
+    let mut report = Report {
+        path: dir.display().to_string(),
+        exists: dir.exists(),
+        registered: entry.is_some(),
+        registry_name: entry.map(|p| p.name.clone()),
+        group: entry.and_then(|p| p.group.clone()),
+        tags: entry.map(|p| p.tags.clone()).unwrap_or_default(),
+        ..Report::default()
+    };
+    if !report.exists {
+        return report;
+    }
+
+    report.has_config = dir.join("CONFIG.toml").exists();
+    report.name = project::load(dir).app.name;
+    if let Some(status) = git::status(dir) {
+        report.branch = status.branch;
+        report.upstream = status.upstream;
+        report.ahead = status.ahead;
+        report.behind = status.behind;
+        report.staged = status.staged;
+        report.unstaged = status.unstaged;
+        report.untracked = status.untracked;
+        report.conflicted = status.conflicted;
+    }
+    report.last_commit = git::output(dir, &["log", "-1", "--format=%h%x00%s%x00%an%x00%cr"]).and_then(|out| {
+        let mut parts = out.split('\0').map(str::to_string);
+        Some(LastCommit { hash: parts.next()?, subject: parts.next()?, author: parts.next()?, date: parts.next()? })
+    });
+    report.remote = git::remote_url(dir);
+    if report.remote.as_deref().is_some_and(|url| url.contains("github.com")) {
+        report.visibility = visibility(dir);
+    }
+    report
+}
+
+/// Asks GitHub whether the repository is public or private; `None` if `gh` can't tell.
+fn visibility(dir: &Path) -> Option<String> {
+    github::output(dir, &["repo", "view", "--json", "visibility", "-q", ".visibility"])
+        .map(|value| value.to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+fn print_report(report: &Report) {
+    let title = report.name.as_deref().or(report.registry_name.as_deref()).unwrap_or("(unnamed)");
+    println!("Project:     {}{}", title, if report.has_config { "" } else { " (no CONFIG.toml)" });
+    println!("Path:        {}{}", report.path, if report.exists { "" } else { " (missing)" });
+    let registry = match &report.registry_name {
+        Some(name) => {
+            let mut line = format!("registered as '{}'", name);
+            if let Some(group) = &report.group {
+                line.push_str(&format!(", group {}", group));
+            }
+            if !report.tags.is_empty() {
+                line.push_str(&format!(", tags {}", report.tags.join(", ")));
+            }
+            line
+        }
+        None => "not registered (run 'odin update' to adopt it)".to_string(),
+    };
+    println!("Registry:    {}", registry);
+    if !report.exists {
+        return;
+    }
+
+    println!("Branch:      {}", report.branch.as_deref().unwrap_or("(detached)"));
+    match &report.upstream {
+        Some(upstream) => println!("Upstream:    {} ({} ahead, {} behind)", upstream, report.ahead, report.behind),
+        None => println!("Upstream:    none"),
+    }
+    let mut changes = vec![
+        format!("{} staged", report.staged),
+        format!("{} unstaged", report.unstaged),
+        format!("{} untracked", report.untracked),
+    ];
+    if report.conflicted > 0 {
+        changes.push(format!("{} conflicted", report.conflicted));
+    }
+    let dirty = report.staged + report.unstaged + report.untracked + report.conflicted > 0;
+    println!("Changes:     {}", if dirty { changes.join(", ") } else { "clean".to_string() });
+    match &report.last_commit {
+        Some(c) => println!("Last commit: {} {} ({}, {})", c.hash, c.subject, c.author, c.date),
+        None => println!("Last commit: none"),
+    }
+    println!("Remote:      {}", report.remote.as_deref().unwrap_or("none"));
+    if report.remote.is_some() {
+        println!("Visibility:  {}", report.visibility.as_deref().unwrap_or("unknown"));
+    }
+}
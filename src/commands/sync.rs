@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use super::{plural, run_git};
+use crate::git::{self, SyncState};
+use crate::registry::{self, Filter, Project};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Pull,
+    Push,
+    Both,
+}
+
+impl Direction {
+    fn pulls(self) -> bool {
+        self != Direction::Push
+    }
+
+    fn pushes(self) -> bool {
+        self != Direction::Pull
+    }
+}
+
+pub struct Options {
+    pub direction: Direction,
+    pub parallel: usize,
+}
+
+enum Outcome {
+    UpToDate,
+    Pulled(usize),
+    Pushed(usize),
+    Skipped(String),
+    Failed(String),
+}
+
+pub fn run(filter: &Filter, options: &Options) {
+    let projects: Vec<Project> = filter.apply(registry::load());
+    if projects.is_empty() {
+        println!("No matching projects.");
+        return;
+    }
+
+    println!("Fetching {} project{}...", projects.len(), plural(projects.len()));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Outcome>>> = Mutex::new(projects.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..options.parallel.max(1).min(projects.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(project) = projects.get(i) else {
+                    break;
+                };
+                let outcome = sync_one(project, options.direction);
+                results.lock().unwrap()[i] = Some(outcome);
+            });
+        }
+    });
+
+    let results: Vec<Outcome> = results.into_inner().unwrap().into_iter().map(|o| o.unwrap()).collect();
+    let width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0).max(7);
+    let (mut pulled, mut pushed, mut current, mut skipped, mut failed) = (0, 0, 0, 0, 0);
+    println!();
+    println!("{:width$}  Result", "Project", width = width);
+    for (project, outcome) in projects.iter().zip(&results) {
+        let result = match outcome {
+            Outcome::UpToDate => {
+                current += 1;
+                "up to date".to_string()
+            }
+            Outcome::Pulled(n) => {
+                pulled += 1;
+                format!("pulled {} commit{}", n, plural(*n))
+            }
+            Outcome::Pushed(n) => {
+                pushed += 1;
+                format!("pushed {} commit{}", n, plural(*n))
+            }
+            Outcome::Skipped(reason) => {
+                skipped += 1;
+                format!("skipped: {}", reason)
+            }
+            Outcome::Failed(error) => {
+                failed += 1;
+                format!("failed: {}", error)
+            }
+        };
+        println!("{:width$}  {}", project.name, result, width = width);
+    }
+    println!();
+    println!(
+        "{} pulled, {} pushed, {} up to date, {} skipped, {} failed.",
+        pulled, pushed, current, skipped, failed
+    );
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Fetches one project and fast-forwards or pushes it if that is all it takes.
+fn sync_one(project: &Project, direction: Direction) -> Outcome {
+    let dir = Path::new(&project.path);
+    if project.is_missing() {
+        return Outcome::Skipped("directory is missing".to_string());
+    }
+    if git::remote_url(dir).is_none() {
+        return Outcome::Skipped("no remote".to_string());
+    }
+    if let Err(e) = run_git(dir, &["fetch", "--quiet", "origin"], false) {
+        return Outcome::Failed(format!("fetch: {}", e));
+    }
+
+    let Some(state) = git::sync_state(dir) else {
+        return Outcome::Failed("upstream branch not found on the remote".to_string());
+    };
+    match state {
+        SyncState::UpToDate => Outcome::UpToDate,
+        SyncState::NoUpstream => {
+            let branch = git::current_branch(dir).unwrap_or_else(|| "HEAD".to_string());
+            Outcome::Skipped(format!("'{}' has no upstream", branch))
+        }
+        SyncState::Diverged { ahead, behind } => {
+            Outcome::Skipped(format!("diverged ({} ahead, {} behind); run 'odin update' there", ahead, behind))
+        }
+        SyncState::Behind(behind) if !direction.pulls() => {
+            Outcome::Skipped(format!("{} commit{} behind; pull not requested", behind, plural(behind)))
+        }
+        SyncState::Ahead(ahead) if !direction.pushes() => {
+            Outcome::Skipped(format!("{} commit{} ahead; push not requested", ahead, plural(ahead)))
+        }
+        SyncState::Behind(behind) => {
+            // A fast-forward would touch the working tree, so leave local edits alone
+            if git::status(dir).is_some_and(|s| s.is_dirty()) {
+                return Outcome::Skipped(format!("{} commit{} behind but has uncommitted changes", behind, plural(behind)));
+            }
+            match run_git(dir, &["merge", "--ff-only", "--quiet", "@{u}"], false) {
+                Ok(()) => Outcome::Pulled(behind),
+                Err(e) => Outcome::Failed(format!("pull: {}", e)),
+            }
+        }
+        SyncState::Ahead(ahead) => match run_git(dir, &["push", "--quiet"], false) {
+            Ok(()) => Outcome::Pushed(ahead),
+            Err(e) => Outcome::Failed(format!("push: {}", e)),
+        },
+    }
+}
@@ -0,0 +1,87 @@
+use clap::Subcommand;
+use super::plural;
+use crate::registry;
+
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// Add a tag to a project
+    Add {
+        /// Name of the project
+        project: String,
+        /// Tag to add
+        tag: String,
+    },
+    /// Remove a tag from a project
+    Remove {
+        /// Name of the project
+        project: String,
+        /// Tag to remove
+        tag: String,
+    },
+    /// List tags in use, or the tags of a single project
+    List {
+        /// Name of the project
+        project: Option<String>,
+    },
+}
+
+pub fn run(action: &TagAction) {
+    let mut projects = registry::load();
+
+    match action {
+        TagAction::Add { project, tag } => {
+            let Some(entry) = registry::find_by_name(&mut projects, project) else {
+                eprintln!("Project '{}' not found in the registry.", project);
+                return;
+            };
+            if entry.has_tag(tag) {
+                println!("Project '{}' is already tagged '{}'.", project, tag);
+                return;
+            }
+            entry.tags.push(tag.to_string());
+            match registry::save(&projects) {
+                Ok(()) => println!("Tagged '{}' with '{}'.", project, tag),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        TagAction::Remove { project, tag } => {
+            let Some(entry) = registry::find_by_name(&mut projects, project) else {
+                eprintln!("Project '{}' not found in the registry.", project);
+                return;
+            };
+            if !entry.has_tag(tag) {
+                println!("Project '{}' is not tagged '{}'.", project, tag);
+                return;
+            }
+            entry.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+            match registry::save(&projects) {
+                Ok(()) => println!("Removed tag '{}' from '{}'.", tag, project),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        TagAction::List { project: Some(project) } => {
+            let Some(entry) = registry::find_by_name(&mut projects, project) else {
+                eprintln!("Project '{}' not found in the registry.", project);
+                return;
+            };
+            if entry.tags.is_empty() {
+                println!("Project '{}' has no tags.", project);
+            } else {
+                println!("{}", entry.tags.join(", "));
+            }
+        }
+        TagAction::List { project: None } => {
+            let mut tags: Vec<&String> = projects.iter().flat_map(|p| &p.tags).collect();
+            tags.sort_by_key(|t| t.to_lowercase());
+            tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+            if tags.is_empty() {
+                println!("No tags in use.");
+                return;
+            }
+            for tag in tags {
+                let count = projects.iter().filter(|p| p.has_tag(tag)).count();
+                println!("{} ({} project{})", tag, count, plural(count));
+            }
+        }
+    }
+}
@@ -1,9 +1,54 @@
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::Path;
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use glob::Pattern;
+use super::{open, plural};
+use crate::commit;
+use crate::git::{self, Change, SyncState};
+use crate::github;
+use crate::project::{self, Divergence};
+use crate::registry::{self, Project};
+use crate::scan;
+
+pub struct Options {
+    /// Stage every change instead of asking which files to include.
+    pub all: bool,
+    /// Only consider changes under these paths.
+    pub paths: Vec<String>,
+    /// Commit even if the pre-commit scan flags secrets or large files.
+    pub allow: bool,
+    /// Commit message, skipping the prompt.
+    pub message: Option<String>,
+    /// Build the message step by step following Conventional Commits.
+    pub conventional: bool,
+    /// Write the message in the editor instead of at the prompt.
+    pub edit: bool,
+    /// Answer yes to every question (push, pull, adopt, create repository).
+    pub yes: bool,
+    /// Never push; commit locally only.
+    pub no_push: bool,
+    /// Pull when the remote has new commits.
+    pub pull: bool,
+    /// Do not offer to adopt a repository that is not an Odin project.
+    pub no_adopt: bool,
+    /// Issues the commit fixes; each gets a `Fixes #N` footer.
+    pub fixes: Vec<u32>,
+}
+
+impl Options {
+    fn push_answer(&self) -> Option<bool> {
+        if self.no_push { Some(false) } else if self.yes { Some(true) } else { None }
+    }
+
+    fn pull_answer(&self) -> Option<bool> {
+        if self.pull || self.yes { Some(true) } else { None }
+    }
+}
 
-pub fn run() {
+pub fn run(options: &Options) {
     // Check if current directory is a git repo
     if !Path::new(".git").exists() {
         eprintln!("Not in a Git repository. Initialize with 'git init' or navigate to a project.");
@@ -12,28 +57,31 @@ pub fn run() {
 
     // Check for CONFIG.toml
     let config_exists = Path::new("CONFIG.toml").exists();
-    let odin_dir = dirs::home_dir().unwrap().join(".odin");
-    let projects_file = odin_dir.join("projects.json");
 
     let current_path = std::env::current_dir().unwrap().canonicalize().unwrap();
     let current_path_str = current_path.display().to_string();
 
     if !config_exists {
-        print!("This directory is not an Odin project. Would you like to add it? (y/N): ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        if !input.trim().eq_ignore_ascii_case("y") {
+        let adopt_preset = if options.no_adopt { Some(false) } else if options.yes { Some(true) } else { None };
+        if !ask("This directory is not an Odin project. Would you like to add it?", adopt_preset, "--yes or --no-adopt") {
             eprintln!("Not in an Odin project directory. Aborting.");
             return;
         }
 
-        // Prompt for project name
-        print!("Enter project name: ");
-        io::stdout().flush().unwrap();
-        let mut name = String::new();
-        io::stdin().read_line(&mut name).unwrap();
-        let name = name.trim();
+        // Prompt for project name, defaulting to the directory name when answering non-interactively
+        let dir_name = current_path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        let name = if options.yes {
+            println!("Project name: {}", dir_name);
+            dir_name
+        } else {
+            require_terminal("Enter project name", "--yes to use the directory name");
+            print!("Enter project name: ");
+            io::stdout().flush().unwrap();
+            let mut name = String::new();
+            io::stdin().read_line(&mut name).unwrap();
+            name.trim().to_string()
+        };
+        let name = name.as_str();
         if name.is_empty() {
             eprintln!("Project name cannot be empty.");
             return;
@@ -47,420 +95,788 @@ pub fn run() {
         }
 
         // Add to projects.json
-        if let Err(e) = fs::create_dir_all(&odin_dir) {
-            eprintln!("Failed to create ~/.odin: {}", e);
+        if let Err(e) = registry::add(Project::new(name, &current_path_str).with_remote(git::remote_url(&current_path))) {
+            eprintln!("{}", e);
             return;
         }
-        let mut projects: Vec<serde_json::Value> = if projects_file.exists() {
-            let data = fs::read_to_string(&projects_file).unwrap_or_default();
-            serde_json::from_str(&data).unwrap_or_default()
-        } else {
-            Vec::new()
+
+        println!("Project '{}' added to Odin management.", name);
+    } else {
+        // Validate the project is in projects.json
+        if registry::exists() && registry::find_by_path(&registry::load(), &current_path).is_none() {
+            eprintln!("Current directory is not tracked as an Odin project.");
+            return;
+        }
+    }
+
+    println!("Checking for uncommitted changes...");
+    let repo = Path::new(".");
+    let Some(changes) = git::changes(repo, &options.paths) else {
+        eprintln!("Failed to check git status.");
+        return;
+    };
+
+    if changes.is_empty() {
+        println!("No uncommitted changes found.");
+    } else {
+        println!("Found uncommitted changes.");
+        let config = project::load(repo);
+        if !stage(repo, &changes, &config.update.exclude, options) {
+            return;
+        }
+    }
+
+    if git::has_staged_changes(repo) {
+        if !scan_staged(repo, options.allow) {
+            return;
+        }
+
+        let Some(message) = commit_message(repo, options) else {
+            return;
         };
-        let new_project = serde_json::json!({
-            "name": name,
-            "path": current_path_str
-        });
-        projects.push(new_project);
-        let json = serde_json::to_string_pretty(&projects).unwrap();
-        if let Err(e) = fs::write(&projects_file, json) {
-            eprintln!("Failed to update projects.json: {}", e);
+        let message = message.as_str();
+
+        println!("Committing changes...");
+        // Git commit
+        let commit_status = Command::new("git")
+            .args(["commit", "-m", message])
+            .status();
+
+        if !commit_status.is_ok_and(|s| s.success()) {
+            eprintln!("Failed to commit changes.");
             return;
         }
+        println!("Committed with message: {}", message.lines().next().unwrap_or(""));
+    }
 
-        println!("Project '{}' added to Odin management.", name);
+    sync(&current_path, options);
+}
 
-        // After adoption, offer to create GitHub repo if no remote
-        let remote_check = Command::new("git")
-            .args(&["remote", "get-url", "origin"])
-            .output();
+/// Pushes or pulls so the current branch matches its upstream, creating a
+/// GitHub repository first if the project has no `origin` remote yet.
+fn sync(current_path: &Path, options: &Options) {
+    let repo = Path::new(".");
+    let Some(remote_url) = git::remote_url(repo) else {
+        println!("No remote repository configured.");
+        if ask("Would you like to create a GitHub repository?", options.push_answer(), "--yes or --no-push") && create_github_repo() {
+            sync(current_path, options);
+        }
+        return;
+    };
+    if let Err(e) = registry::record_remote(current_path, &remote_url) {
+        eprintln!("{}", e);
+    }
 
-        if remote_check.is_err() {
-            print!("No remote repository found. Would you like to create a GitHub repository and push? (y/N): ");
-            io::stdout().flush().unwrap();
-            let mut create_remote = String::new();
-            io::stdin().read_line(&mut create_remote).unwrap();
-            if create_remote.trim().eq_ignore_ascii_case("y") {
-                crate::commands::ensure_gh_installed();
-
-                // Check GitHub auth
-                let auth_check = Command::new("gh").args(&["auth", "status"]).output();
-                if !auth_check.map_or(false, |o| o.status.success()) {
-                    eprintln!("GitHub not authenticated. Run 'odin config' first.");
-                    return;
-                }
+    let config = project::load(repo);
+    println!("Project: {}", config.name());
+    println!("Remote: {}", remote_url);
 
-                println!("Creating GitHub repository...");
-                // Create GitHub repo
-                let output = Command::new("gh")
-                    .args(&["repo", "create", &name, "--public", "--source=.", "--remote=origin", "--push"])
-                    .current_dir(".")
-                    .output();
+    println!("Fetching from remote...");
+    let fetch_status = Command::new("git")
+        .args(["fetch", "origin"])
+        .status();
+    if !fetch_status.is_ok_and(|s| s.success()) {
+        eprintln!("Failed to fetch from remote.");
+        return;
+    }
 
-                match output {
-                    Ok(result) if result.status.success() => {
-                        println!("GitHub repository created and pushed.");
-                    }
-                    Ok(result) => {
-                        eprintln!("Failed to create GitHub repo: {}", String::from_utf8_lossy(&result.stderr));
-                    }
-                    Err(e) => {
-                        eprintln!("Error running gh command: {}", e);
-                    }
-                }
+    let Some(state) = git::sync_state(repo) else {
+        let upstream = git::upstream(repo).unwrap_or_default();
+        eprintln!("Could not compare with upstream '{}'. Has it been deleted on the remote?", upstream);
+        return;
+    };
+
+    match state {
+        SyncState::UpToDate => {
+            println!("Local repository is up to date with {}.", git::upstream(repo).unwrap_or_default());
+        }
+        SyncState::Ahead(ahead) => {
+            let question = format!("Local is {} commit{} ahead of remote. Push?", ahead, plural(ahead));
+            if ask(&question, options.push_answer(), "--yes or --no-push") {
+                push(&["push"]);
+            } else {
+                println!("Push cancelled.");
             }
         }
-    } else {
-        // Validate the project is in projects.json
-        if projects_file.exists() {
-            let data = fs::read_to_string(&projects_file).unwrap_or_default();
-            let projects: Vec<serde_json::Value> = serde_json::from_str(&data).unwrap_or_default();
-            let is_tracked = projects.iter().any(|p| {
-                if let Some(path) = p.get("path") {
-                    if let Some(path_str) = path.as_str() {
-                        Path::new(path_str).canonicalize().ok() == Some(current_path.clone())
+        SyncState::Behind(behind) => {
+            let question = format!("Local is {} commit{} behind remote. Pull?", behind, plural(behind));
+            if ask(&question, options.pull_answer(), "--pull or --yes") {
+                pull_with_stash(repo, options, || {
+                    println!("Pulling from remote...");
+                    let pull_status = Command::new("git")
+                        .args(["pull", "--ff-only"])
+                        .status();
+                    if pull_status.is_ok_and(|s| s.success()) {
+                        println!("Pulled from remote.");
+                        true
                     } else {
+                        eprintln!("Failed to pull from remote.");
                         false
                     }
+                });
+            }
+        }
+        SyncState::Diverged { ahead, behind } => {
+            println!("Local and remote have diverged ({} ahead, {} behind).", ahead, behind);
+            if !resolve_divergence(repo, config.update.divergence, options) {
+                return;
+            }
+            if let Some(SyncState::Ahead(ahead)) = git::sync_state(repo) {
+                let question = format!("Local is now {} commit{} ahead of remote. Push?", ahead, plural(ahead));
+                if ask(&question, options.push_answer(), "--yes or --no-push") {
+                    push(&["push"]);
                 } else {
-                    false
+                    println!("Push cancelled.");
                 }
-            });
-            if !is_tracked {
-                eprintln!("Current directory is not tracked as an Odin project.");
-                return;
             }
         }
+        SyncState::NoUpstream => offer_upstream(repo, options),
     }
+}
 
-    println!("Checking for uncommitted changes...");
-    let status_output = Command::new("git")
-        .args(&["status", "--porcelain"])
-        .output();
-
-    let has_changes = match status_output {
-        Ok(output) => !output.stdout.is_empty(),
-        Err(e) => {
-            eprintln!("Failed to check git status: {}", e);
-            return;
+/// Integrates the remote commits by rebase or merge, as chosen by the user.
+/// Returns true if the branch now contains the upstream commits.
+fn resolve_divergence(repo: &Path, configured: Option<Divergence>, options: &Options) -> bool {
+    let default = configured.unwrap_or(Divergence::Abort);
+    let hint = match default {
+        Divergence::Rebase => "(R)ebase, (m)erge or (a)bort? ",
+        Divergence::Merge => "(r)ebase, (M)erge or (a)bort? ",
+        Divergence::Abort => "(r)ebase, (m)erge or (A)bort? ",
+    };
+    let choice = if options.pull_answer().is_some() {
+        // Non-interactive pulls follow the project's configured strategy
+        println!("{}{}", hint, divergence_name(default));
+        default
+    } else {
+        require_terminal("Rebase, merge or abort", "--pull or --yes to use the [update] divergence setting");
+        print!("{}", hint);
+        io::stdout().flush().unwrap();
+        let mut choice = String::new();
+        io::stdin().read_line(&mut choice).unwrap();
+        match choice.trim().to_lowercase().as_str() {
+            "" => default,
+            "r" => Divergence::Rebase,
+            "m" => Divergence::Merge,
+            _ => Divergence::Abort,
         }
     };
 
-    if !has_changes {
-        println!("No uncommitted changes found.");
+    if choice == Divergence::Abort {
+        println!("Nothing was changed. Resolve manually with 'git pull --rebase' or 'git pull --no-rebase', then run 'odin update' again.");
+        return false;
+    }
+    pull_with_stash(repo, options, || pull_divergent(repo, choice))
+}
+
+/// Runs `git pull` with the chosen strategy and deals with any conflicts it stops on.
+fn pull_divergent(repo: &Path, choice: Divergence) -> bool {
+    let (args, name, abort_args, continue_hint) = match choice {
+        Divergence::Rebase => (
+            ["pull", "--rebase"],
+            "Rebase",
+            ["rebase", "--abort"],
+            "git add <files> && git rebase --continue",
+        ),
+        Divergence::Merge => (
+            ["pull", "--no-rebase"],
+            "Merge",
+            ["merge", "--abort"],
+            "git add <files> && git commit",
+        ),
+        Divergence::Abort => return false,
+    };
+
+    println!("Pulling from remote ({})...", name.to_lowercase());
+    let pull_status = Command::new("git").args(args).status();
+    if pull_status.is_ok_and(|s| s.success()) {
+        println!("{} completed.", name);
+        return true;
+    }
+
+    let conflicts = git::conflicted_files(repo);
+    if conflicts.is_empty() {
+        eprintln!("{} failed. See the git output above.", name);
+        return false;
+    }
+
+    println!("{} stopped with conflicts in:", name);
+    for file in &conflicts {
+        println!("  {}", file);
+    }
+    // Without a terminal nobody can resolve the conflicts, so always restore the branch
+    let leave = io::stdin().is_terminal() && {
+        print!("(a)bort and restore the branch, or (l)eave it to resolve manually? ");
+        io::stdout().flush().unwrap();
+        let mut next = String::new();
+        io::stdin().read_line(&mut next).unwrap();
+        next.trim().eq_ignore_ascii_case("l")
+    };
+    if leave {
+        println!("Left the {} in progress. Resolve the files above, run '{}', then 'odin update' to push.", name.to_lowercase(), continue_hint);
+        return false;
+    }
+
+    let abort_status = Command::new("git").args(abort_args).status();
+    if abort_status.is_ok_and(|s| s.success()) {
+        println!("{} aborted. Your branch is back where it was and nothing was pushed.", name);
     } else {
-        println!("Found uncommitted changes.");
-        println!("Staging changes...");
-        // Git add all changes
-        let add_status = Command::new("git")
-            .args(&["add", "."])
-            .status();
+        eprintln!("Failed to abort. Run 'git {}' manually.", abort_args.join(" "));
+    }
+    false
+}
 
-        if !add_status.map_or(false, |s| s.success()) {
-            eprintln!("Failed to stage files.");
-            return;
+/// Runs `pull`, first stashing uncommitted changes to tracked files (which
+/// would make git refuse to pull) and popping them back afterwards. The stash
+/// is only dropped once it has been applied cleanly.
+fn pull_with_stash(repo: &Path, options: &Options, pull: impl FnOnce() -> bool) -> bool {
+    let dirty = git::status(repo).is_some_and(|s| s.staged + s.unstaged + s.conflicted > 0);
+    if !dirty {
+        return pull();
+    }
+    let question = "You have uncommitted changes. Stash them, pull, then restore them?";
+    if !ask(question, options.pull_answer(), "--pull or --yes") {
+        println!("Pull cancelled. Commit or stash your changes, then run 'odin update' again.");
+        return false;
+    }
+    let Some(stash) = git::stash_push(repo, "odin update: changes set aside while pulling") else {
+        eprintln!("Failed to stash your changes. Nothing was pulled.");
+        return false;
+    };
+    println!("Stashed uncommitted changes.");
+
+    let pulled = pull();
+    if git::operation_in_progress(repo) {
+        println!("Your uncommitted changes are still stashed. Run 'git stash pop' once the pull is finished.");
+        return pulled;
+    }
+
+    let Some(stash_ref) = git::stash_ref(repo, &stash) else {
+        eprintln!("Could not find the stash to restore. Recover your changes with 'git stash apply {}'.", stash);
+        return pulled;
+    };
+    let pop_status = Command::new("git").args(["stash", "pop", "-q", &stash_ref]).status();
+    if pop_status.is_ok_and(|s| s.success()) {
+        println!("Restored your uncommitted changes.");
+        return pulled;
+    }
+
+    let conflicts = git::conflicted_files(repo);
+    if conflicts.is_empty() {
+        eprintln!("Could not restore your uncommitted changes. They are kept in {}; apply them with 'git stash pop'.", stash_ref);
+    } else {
+        println!("Restoring your uncommitted changes conflicted in:");
+        for file in &conflicts {
+            println!("  {}", file);
         }
-        println!("Staged all changes.");
+        println!("Your changes are kept in {}. Resolve the files above, then run 'git stash drop {}'.", stash_ref, stash_ref);
+    }
+    pulled
+}
 
-        // Prompt for commit message
-        print!("Enter commit message: ");
-        io::stdout().flush().unwrap();
-        let mut message = String::new();
-        io::stdin().read_line(&mut message).unwrap();
-        let message = message.trim();
+/// Creates the GitHub repository for the project and pushes to it. Returns
+/// true if an existing repository was linked as `origin` instead, which still
+/// needs to be synced.
+fn create_github_repo() -> bool {
+    // Get project name from CONFIG.toml
+    let project_name = project::load(Path::new(".")).app.name.unwrap_or_else(|| "unknown".to_string());
 
-        if message.is_empty() {
-            eprintln!("Commit message cannot be empty.");
-            return;
+    crate::commands::ensure_gh_installed();
+
+    // Check GitHub auth
+    let auth_check = Command::new("gh").args(["auth", "status"]).output();
+    if !auth_check.is_ok_and(|o| o.status.success()) {
+        eprintln!("GitHub not authenticated. Run 'odin config' first.");
+        return false;
+    }
+
+    println!("Creating GitHub repository...");
+    match gh_repo_create(&project_name) {
+        Ok(()) => {
+            println!("GitHub repository created and pushed.");
+            false
+        }
+        Err(stderr) if stderr.contains("Name already exists") => name_taken(&project_name),
+        Err(stderr) => {
+            eprintln!("Failed to create GitHub repo: {}", stderr);
+            false
         }
+    }
+}
 
-        println!("Committing changes...");
-        // Git commit
-        let commit_status = Command::new("git")
-            .args(&["commit", "-m", message])
-            .status();
+/// Runs `gh repo create` for the current directory, returning gh's error output on failure.
+fn gh_repo_create(name: &str) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args(["repo", "create", name, "--public", "--source=.", "--remote=origin", "--push"])
+        .output()
+        .map_err(|e| format!("Error running gh command: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
 
-        if !commit_status.map_or(false, |s| s.success()) {
-            eprintln!("Failed to commit changes.");
-            return;
+/// Offers safe ways forward when the repository name is already taken: link
+/// the existing repository, pick another name, or replace it after a typed
+/// confirmation. Returns true if the existing repository was linked.
+fn name_taken(name: &str) -> bool {
+    let user = github::current_user();
+    let full_name = user.as_ref().map_or(name.to_string(), |u| format!("{}/{}", u, name));
+    let info = github::repo_info(&full_name);
+    match &info {
+        Some(info) => println!("Repository {} already exists ({}).", info.full_name, describe_repo(info)),
+        None => println!("Repository '{}' already exists.", name),
+    }
+    if !io::stdin().is_terminal() {
+        eprintln!("Run 'odin update' in a terminal to link it or choose another name.");
+        return false;
+    }
+
+    enum Choice {
+        Link,
+        Rename,
+        Replace,
+        Cancel,
+    }
+    let ours = info.as_ref().is_some_and(|i| user.as_deref() == Some(i.owner.as_str()));
+    let mut choices = Vec::new();
+    if ours {
+        choices.push(("Link the existing repository as origin", Choice::Link));
+    }
+    choices.push(("Create the repository under another name", Choice::Rename));
+    if info.is_some() {
+        choices.push(("Delete the existing repository and create a new one", Choice::Replace));
+    }
+    choices.push(("Cancel", Choice::Cancel));
+    let labels: Vec<&str> = choices.iter().map(|(label, _)| *label).collect();
+    let Some(selected) = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What would you like to do?")
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .unwrap()
+    else {
+        println!("Cancelled.");
+        return false;
+    };
+
+    match (&choices[selected].1, info) {
+        (Choice::Link, Some(info)) => link_existing(&info),
+        (Choice::Rename, _) => {
+            create_under_other_name(name, user.as_deref());
+            false
+        }
+        (Choice::Replace, Some(info)) => {
+            replace_existing(name, &info);
+            false
+        }
+        _ => {
+            println!("Cancelled.");
+            false
         }
-        println!("Committed with message: {}", message);
+    }
+}
+
+fn describe_repo(info: &github::RepoInfo) -> String {
+    let commits = match info.commits {
+        Some(n) => format!("{} commit{}", n, plural(n as usize)),
+        None => "no commits".to_string(),
+    };
+    format!("{} star{}, {}", info.stars, plural(info.stars as usize), commits)
+}
+
+/// Adds the repository as `origin` if it is empty or shares history with
+/// the local branch; otherwise leaves the remotes as they were.
+fn link_existing(info: &github::RepoInfo) -> bool {
+    let repo = Path::new(".");
+    let url = github::clone_url(&info.full_name);
+    if git::output(repo, &["remote", "add", "origin", &url]).is_none() {
+        eprintln!("Failed to add {} as origin.", url);
+        return false;
+    }
+    println!("Fetching {}...", info.full_name);
+    if git::output(repo, &["fetch", "-q", "origin"]).is_none() {
+        eprintln!("Failed to fetch {}. It was not linked.", info.full_name);
+        git::output(repo, &["remote", "remove", "origin"]);
+        return false;
     }
 
-    // Check if there's a remote before pushing
-    let remote_check = Command::new("git")
-        .args(&["remote", "get-url", "origin"])
-        .output();
+    let remote_branches = git::output(repo, &["for-each-ref", "--format=%(refname:short)", "refs/remotes/origin"]).unwrap_or_default();
+    let related = remote_branches.is_empty()
+        || remote_branches.lines().any(|branch| git::output(repo, &["merge-base", "HEAD", branch]).is_some());
+    if !related {
+        git::output(repo, &["remote", "remove", "origin"]);
+        println!(
+            "{} has no history in common with this project, so it was not linked. Choose another name, or merge it by hand.",
+            info.full_name
+        );
+        return false;
+    }
 
-    if let Ok(output) = remote_check {
-        if output.status.success() {
-            let remote_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if let Some(branch) = git::current_branch(repo)
+        && git::ref_exists(repo, &format!("origin/{}", branch))
+    {
+        git::output(repo, &["branch", "-q", &format!("--set-upstream-to=origin/{}", branch)]);
+    }
+    println!("Linked {} as origin.", info.full_name);
+    true
+}
 
-        // Get project name from CONFIG.toml
-        let config_content = fs::read_to_string("CONFIG.toml").unwrap_or_default();
-        let project_name = if let Some(line) = config_content.lines().find(|l| l.starts_with("name = ")) {
-            line.split('"').nth(1).unwrap_or("Unknown").to_string()
-        } else {
-            "Unknown".to_string()
-        };
+/// Suggests names that are still free under `user` and creates the repository with the chosen one.
+fn create_under_other_name(name: &str, user: Option<&str>) {
+    let free = |candidate: &String| user.is_none_or(|u| !github::repo_exists(&format!("{}/{}", u, candidate)));
+    let candidates = (2..=9)
+        .map(|n| format!("{}-{}", name, n))
+        .chain(["app", "project"].iter().map(|suffix| format!("{}-{}", name, suffix)));
+    let mut items: Vec<String> = candidates.filter(free).take(3).collect();
+    items.push("Enter another name".to_string());
+    let Some(selected) = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Available names")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .unwrap()
+    else {
+        println!("Cancelled.");
+        return;
+    };
 
-        println!("Project: {}", project_name);
-        println!("Remote: {}", remote_url);
-        print!("Proceed with push? (y/N): ");
-        io::stdout().flush().unwrap();
-        let mut confirm = String::new();
-        io::stdin().read_line(&mut confirm).unwrap();
-        if !confirm.trim().eq_ignore_ascii_case("y") {
-            println!("Push cancelled.");
+    let new_name = if selected + 1 == items.len() {
+        let entered: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Repository name")
+            .allow_empty(true)
+            .interact_text()
+            .unwrap();
+        entered.trim().to_string()
+    } else {
+        items[selected].clone()
+    };
+    if new_name.is_empty() {
+        eprintln!("Name cannot be empty. Cancelled.");
+        return;
+    }
+    match gh_repo_create(&new_name) {
+        Ok(()) => println!("GitHub repository '{}' created and pushed.", new_name),
+        Err(stderr) => eprintln!("Failed to create repository '{}': {}", new_name, stderr),
+    }
+}
+
+/// Deletes the existing repository and recreates it, but only after showing
+/// what would be lost, offering a backup and having the full name typed back.
+fn replace_existing(name: &str, info: &github::RepoInfo) {
+    println!(
+        "Deleting {} ({}) permanently removes its code, issues, pull requests and releases.",
+        info.full_name,
+        describe_repo(info)
+    );
+    let backup = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Back up a mirror clone to ~/.odin/trash first?")
+        .default(true)
+        .interact()
+        .unwrap();
+    if backup {
+        let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let dest = registry::odin_dir().join("trash").join(format!("{}-{}.git", info.full_name.replace('/', "-"), stamp));
+        println!("Backing up to {}...", dest.display());
+        let cloned = fs::create_dir_all(dest.parent().unwrap()).is_ok()
+            && Command::new("git")
+                .args(["clone", "-q", "--mirror", &github::clone_url(&info.full_name)])
+                .arg(&dest)
+                .status()
+                .is_ok_and(|s| s.success());
+        if !cloned {
+            eprintln!("Backup failed. Nothing was deleted.");
             return;
         }
+    }
 
-        println!("Pushing to remote...");
-        // Git push
-        let push_status = Command::new("git")
-            .args(&["push"])
-            .status();
+    let typed: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Type {} to confirm deletion", info.full_name))
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+    if typed.trim() != info.full_name {
+        println!("Name did not match. Nothing was deleted.");
+        return;
+    }
 
-        if push_status.map_or(false, |s| s.success()) {
-            println!("Pushed to remote repository.");
-        } else {
-            eprintln!("Failed to push to remote.");
+    println!("Deleting {}...", info.full_name);
+    let delete = Command::new("gh").args(["repo", "delete", &info.full_name, "--yes"]).output();
+    match delete {
+        Ok(out) if out.status.success() => {}
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            if stderr.contains("delete_repo") {
+                eprintln!("Deleting needs the delete_repo scope. Grant it with 'gh auth refresh -h github.com -s delete_repo', then run 'odin update' again.");
+            } else {
+                eprintln!("Failed to delete {}: {}", info.full_name, stderr.trim());
+            }
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error running gh command: {}", e);
+            return;
         }
+    }
+    match gh_repo_create(name) {
+        Ok(()) => println!("GitHub repository recreated and pushed."),
+        Err(stderr) => eprintln!("Failed to recreate repository: {}", stderr),
+    }
 }
 
-    println!("Checking remote synchronization...");
-    // Check if remote exists
-    let remotes_output = Command::new("git")
-        .args(&["remote"])
-        .output();
-
-    let has_remote = remotes_output.ok()
-        .map(|o| String::from_utf8_lossy(&o.stdout).contains("origin"))
-        .unwrap_or(false);
-
-    if has_remote {
-        let remote_check = Command::new("git")
-            .args(&["remote", "get-url", "origin"])
-            .output();
-
-        if let Ok(output) = remote_check {
-            if output.status.success() {
-                let _remote_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-                println!("Fetching from remote...");
-                // Fetch remote
-                let fetch_status = Command::new("git")
-                    .args(&["fetch", "origin"])
-                    .status();
-
-                if fetch_status.map_or(false, |s| s.success()) {
-                // Check if ahead or behind
-                let ahead_output = Command::new("git")
-                    .args(&["rev-list", "--count", "HEAD..origin/master"])
-                    .output();
-
-                let behind_output = Command::new("git")
-                    .args(&["rev-list", "--count", "origin/master..HEAD"])
-                    .output();
-
-                let ahead = ahead_output.ok()
-                    .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse::<i32>().ok())
-                    .unwrap_or(0);
-
-                let behind = behind_output.ok()
-                    .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse::<i32>().ok())
-                    .unwrap_or(0);
-
-                if ahead > 0 && behind == 0 {
-                    print!("Local is {} commits ahead. Push to remote? (y/N): ", ahead);
-                    io::stdout().flush().unwrap();
-                    let mut push_confirm = String::new();
-                    io::stdin().read_line(&mut push_confirm).unwrap();
-                    if push_confirm.trim().eq_ignore_ascii_case("y") {
-                        println!("Pushing to remote...");
-                        let push_status = Command::new("git")
-                            .args(&["push"])
-                            .status();
-                        if push_status.map_or(false, |s| s.success()) {
-                            println!("Pushed to remote.");
-                        } else {
-                            eprintln!("Failed to push to remote.");
-                        }
-                    }
-                } else if behind > 0 && ahead == 0 {
-                    print!("Local is {} commits behind. Pull from remote? (y/N): ", behind);
-                    io::stdout().flush().unwrap();
-                    let mut pull_confirm = String::new();
-                    io::stdin().read_line(&mut pull_confirm).unwrap();
-                    if pull_confirm.trim().eq_ignore_ascii_case("y") {
-                        println!("Pulling from remote...");
-                        let pull_status = Command::new("git")
-                            .args(&["pull"])
-                            .status();
-                        if pull_status.map_or(false, |s| s.success()) {
-                            println!("Pulled from remote.");
-                        } else {
-                            eprintln!("Failed to pull from remote.");
-                        }
-                    }
-                } else if ahead > 0 && behind > 0 {
-                    println!("Local and remote have diverged ({} ahead, {} behind). Manual resolution needed.", ahead, behind);
-                    } else {
-                        println!("Local repository is up to date with remote.");
-                    }
-                } else {
-                    eprintln!("Failed to fetch from remote.");
-                }
-            }
+/// Stages the changes picked by the user, or all of them with `--all` or explicit
+/// paths. Files matching the `[update] exclude` globs are never staged.
+/// Returns false if the user cancelled or staging failed.
+fn stage(repo: &Path, changes: &[Change], exclude: &[String], options: &Options) -> bool {
+    let patterns: Vec<Pattern> = exclude
+        .iter()
+        .filter_map(|p| Pattern::new(p).map_err(|e| eprintln!("Ignoring invalid exclude pattern '{}': {}", p, e)).ok())
+        .collect();
+    let (excluded, candidates): (Vec<&Change>, Vec<&Change>) =
+        changes.iter().partition(|c| is_excluded(&c.path, &patterns));
+
+    if !excluded.is_empty() {
+        println!("Skipping {} excluded file{}:", excluded.len(), plural(excluded.len()));
+        for change in &excluded {
+            println!("  {} {}", change.code, change.path);
         }
+    }
+
+    // With nothing left to pick from, fall through so excluded files are still unstaged below
+    let selected: Vec<&Change> = if options.all || !options.paths.is_empty() || candidates.is_empty() {
+        candidates.clone()
     } else {
-        println!("No remote repository configured.");
-        print!("Would you like to create a GitHub repository? (y/N): ");
-        io::stdout().flush().unwrap();
-        let mut create_confirm = String::new();
-        io::stdin().read_line(&mut create_confirm).unwrap();
-        if create_confirm.trim().eq_ignore_ascii_case("y") {
-            // Get project name from CONFIG.toml
-            let config_content = fs::read_to_string("CONFIG.toml").unwrap_or_default();
-            let project_name = if let Some(line) = config_content.lines().find(|l| l.starts_with("name = ")) {
-                line.split('"').nth(1).unwrap_or("unknown").to_string()
-            } else {
-                "unknown".to_string()
+        let defaults: Vec<bool> = candidates.iter().map(|c| !c.is_untracked()).collect();
+        if options.yes {
+            // Accept the picker's preselection: tracked changes, no untracked files
+            let chosen: Vec<&Change> = candidates.iter().zip(&defaults).filter(|(_, d)| **d).map(|(c, _)| *c).collect();
+            let skipped = candidates.len() - chosen.len();
+            if skipped > 0 {
+                println!("Leaving {} untracked file{} unstaged (pass --all to include them).", skipped, plural(skipped));
+            }
+            chosen
+        } else {
+            require_terminal("Select files to commit", "--all, --yes or the paths to stage");
+            let items: Vec<String> = candidates.iter().map(|c| format!("{} {}", c.code, c.path)).collect();
+            let picked = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select files to commit (space to toggle, enter to confirm)")
+                .items(&items)
+                .defaults(&defaults)
+                .interact_opt()
+                .unwrap();
+            let Some(picked) = picked else {
+                println!("Cancelled.");
+                return false;
             };
+            picked.into_iter().map(|i| candidates[i]).collect()
+        }
+    };
 
-            crate::commands::ensure_gh_installed();
+    // Whatever is not selected must not end up in the commit, even if it was staged
+    // before, including files outside the path arguments.
+    let keep: Vec<&str> = selected.iter().flat_map(|c| c.paths()).collect();
+    let staged = git::staged_files(repo);
+    let unstage: Vec<&str> = staged.iter().map(String::as_str).filter(|p| !keep.contains(p)).collect();
+    if !unstage.is_empty() {
+        let _ = Command::new("git").args(["reset", "-q", "--"]).args(&unstage).status();
+    }
 
-            // Check GitHub auth
-            let auth_check = Command::new("gh").args(&["auth", "status"]).output();
-            if !auth_check.map_or(false, |o| o.status.success()) {
-                eprintln!("GitHub not authenticated. Run 'odin config' first.");
-                return;
-            }
+    if selected.is_empty() {
+        println!("No files selected.");
+        return true;
+    }
 
-            println!("Creating GitHub repository...");
-            // Create GitHub repo
-            let output = Command::new("gh")
-                .args(&["repo", "create", &project_name, "--public", "--source=.", "--remote=origin", "--push"])
-                .current_dir(".")
-                .output();
+    println!("Staging changes...");
+    let add_status = Command::new("git")
+        .args(["add", "-A", "--"])
+        .args(selected.iter().flat_map(|c| c.paths()))
+        .status();
+    if !add_status.is_ok_and(|s| s.success()) {
+        eprintln!("Failed to stage files.");
+        return false;
+    }
+    println!("Staged {} file{}.", selected.len(), plural(selected.len()));
+    true
+}
 
-            match output {
-                Ok(result) if result.status.success() => {
-                    println!("GitHub repository created and pushed.");
-                }
-                Ok(result) => {
-                    let stderr = String::from_utf8_lossy(&result.stderr);
-                    if stderr.contains("Name already exists") {
-                        print!("Repository '{}' already exists. (n)ew name, (o)verwrite, (c)ancel? ", project_name);
-                        io::stdout().flush().unwrap();
-                        let mut choice = String::new();
-                        io::stdin().read_line(&mut choice).unwrap();
-                        let choice = choice.trim().to_lowercase();
-                        match choice.as_str() {
-                            "n" => {
-                                print!("Enter new repository name: ");
-                                io::stdout().flush().unwrap();
-                                let mut new_name = String::new();
-                                io::stdin().read_line(&mut new_name).unwrap();
-                                let new_name = new_name.trim();
-                                if new_name.is_empty() {
-                                    eprintln!("Name cannot be empty. Cancelled.");
-                                    return;
-                                }
-                                // Retry with new name
-                                let output2 = Command::new("gh")
-                                    .args(&["repo", "create", &new_name, "--public", "--source=.", "--remote=origin", "--push"])
-                                    .current_dir(".")
-                                    .output();
-                                match output2 {
-                                    Ok(r) if r.status.success() => {
-                                        println!("GitHub repository '{}' created and pushed.", new_name);
-                                    }
-                                    _ => {
-                                        eprintln!("Failed to create repository with new name.");
-                                    }
-                                }
-                            }
-                            "o" => {
-                                println!("Deleting existing repository...");
-                                let delete_output = Command::new("gh")
-                                    .args(&["repo", "delete", &project_name, "--yes"])
-                                    .output();
-                                if let Ok(del_out) = delete_output {
-                                    if del_out.status.success() {
-                                        println!("Recreating repository...");
-                                        let output3 = Command::new("gh")
-                                            .args(&["repo", "create", &project_name, "--public", "--source=.", "--remote=origin", "--push"])
-                                            .current_dir(".")
-                                            .output();
-                                        match output3 {
-                                            Ok(r) if r.status.success() => {
-                                                println!("GitHub repository recreated and pushed.");
-                                            }
-                                            _ => {
-                                                eprintln!("Failed to recreate repository.");
-                                            }
-                                        }
-                                    } else {
-                                        let stderr = String::from_utf8_lossy(&del_out.stderr);
-                                        eprintln!("Failed to delete existing repository: {}", stderr);
-                                        if stderr.contains("delete_repo") {
-                                            println!("Refreshing auth to add delete_repo scope...");
-                                            let refresh_status = Command::new("gh")
-                                                .args(&["auth", "refresh", "-h", "github.com", "-s", "delete_repo"])
-                                                .status();
-                                            if refresh_status.map_or(false, |s| s.success()) {
-                                                println!("Auth refreshed. Retrying deletion...");
-                                                // Retry delete
-                                                let retry_delete = Command::new("gh")
-                                                    .args(&["repo", "delete", &project_name, "--yes"])
-                                                    .output();
-                                                if let Ok(retry_out) = retry_delete {
-                                                    if retry_out.status.success() {
-                                                        println!("Recreating repository...");
-                                                        let output3 = Command::new("gh")
-                                                            .args(&["repo", "create", &project_name, "--public", "--source=.", "--remote=origin", "--push"])
-                                                            .current_dir(".")
-                                                            .output();
-                                                        match output3 {
-                                                            Ok(r) if r.status.success() => {
-                                                                println!("GitHub repository recreated and pushed.");
-                                                            }
-                                                            _ => {
-                                                                eprintln!("Failed to recreate repository.");
-                                                            }
-                                                        }
-                                                    } else {
-                                                        let retry_stderr = String::from_utf8_lossy(&retry_out.stderr);
-                                                        eprintln!("Retry delete failed: {}", retry_stderr);
-                                                    }
-                                                } else {
-                                                    eprintln!("Failed to retry delete.");
-                                                }
-                                            } else {
-                                                eprintln!("Auth refresh failed. Run manually: gh auth refresh -h github.com -s delete_repo");
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    eprintln!("Failed to run delete command.");
-                                }
-                            }
-                            _ => {
-                                println!("Cancelled.");
-                            }
-                        }
-                    } else {
-                        eprintln!("Failed to create GitHub repo: {}", stderr);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error running gh command: {}", e);
-                }
-            }
+/// Gets the commit message from `-m`, the guided Conventional Commits prompt or
+/// a plain prompt, and checks it against the project's `[commit]` rules.
+fn commit_message(repo: &Path, options: &Options) -> Option<String> {
+    let config = project::load(repo).commit;
+
+    let suggestion = commit::suggest(repo);
+
+    let message = match &options.message {
+        Some(message) => message.trim().to_string(),
+        None if options.edit => {
+            let initial = suggestion.as_ref().map(|s| s.message(&config)).unwrap_or_default();
+            edit_message(repo, &initial)?
+        }
+        // The suggestion follows the project's format, so it works for conventional projects too
+        None if options.yes && suggestion.is_some() => {
+            let message = suggestion.as_ref().map(|s| s.message(&config)).unwrap_or_default();
+            println!("Using suggested commit message: {}", message);
+            message
+        }
+        None if options.conventional || config.is_conventional() => {
+            require_terminal("Guided commit message", "-m <message>");
+            let Some(message) = commit::guided(&config, suggestion.as_ref()) else {
+                println!("Cancelled.");
+                return None;
+            };
+            message
+        }
+        None => {
+            require_terminal("Commit message", "-m <message>");
+            let initial = suggestion.as_ref().map(|s| s.message(&config)).unwrap_or_default();
+            let message: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Commit message")
+                .with_initial_text(initial)
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            message.trim().to_string()
+        }
+    };
+
+    let message = commit::with_fixes(&message, &options.fixes);
+    if let Err(problems) = commit::validate(&message, &config) {
+        for problem in problems {
+            eprintln!("{}", problem);
+        }
+        if !message.is_empty() {
+            eprintln!("Commit aborted; your changes are still staged.");
+        }
+        return None;
+    }
+    Some(message)
+}
+
+/// Opens the editor on a message file pre-filled with `initial` and the list of
+/// staged files. Lines starting with `#` are dropped, as with `git commit`.
+fn edit_message(repo: &Path, initial: &str) -> Option<String> {
+    let git_dir = git::output(repo, &["rev-parse", "--git-dir"]).unwrap_or_else(|| ".git".to_string());
+    let path = repo.join(git_dir).join("ODIN_COMMIT_EDITMSG");
+    let staged = git::output(repo, &["diff", "--cached", "--name-status"]).unwrap_or_default();
+    let mut content = format!("{}\n\n# Write the commit message above. Lines starting with '#' are ignored.\n# Staged changes:\n", initial);
+    for line in staged.lines() {
+        content.push_str(&format!("#   {}\n", line.replace('\t', " ")));
+    }
+    if let Err(e) = fs::write(&path, content) {
+        eprintln!("Failed to write {}: {}", path.display(), e);
+        return None;
+    }
+
+    let status = open::editor_command(&path).status();
+    if !status.is_ok_and(|s| s.success()) {
+        eprintln!("Editor exited with an error. Commit aborted; your changes are still staged.");
+        return None;
+    }
+    let edited = fs::read_to_string(&path).unwrap_or_default();
+    let _ = fs::remove_file(&path);
+    let message: Vec<&str> = edited.lines().filter(|l| !l.starts_with('#')).collect();
+    Some(message.join("\n").trim().to_string())
+}
+
+/// Runs the secret and large-file scan over the staged changes.
+/// Returns false if the commit should be blocked.
+fn scan_staged(repo: &Path, allow: bool) -> bool {
+    let config = project::load(repo);
+    let max_file_size = match config.update.max_file_size.as_deref().map(scan::parse_size) {
+        Some(Some(size)) => size,
+        Some(None) => {
+            eprintln!("Invalid [update] max_file_size in CONFIG.toml; using the default.");
+            scan::DEFAULT_MAX_FILE_SIZE
+        }
+        None => scan::DEFAULT_MAX_FILE_SIZE,
+    };
+
+    let findings = scan::staged(repo, max_file_size);
+    if findings.is_empty() {
+        return true;
+    }
+
+    println!("The scan flagged {} problem{} in the staged changes:", findings.len(), plural(findings.len()));
+    for finding in &findings {
+        match finding.line {
+            Some(line) => println!("  {}:{}: {}", finding.path, line, finding.reason),
+            None => println!("  {}: {}", finding.path, finding.reason),
         }
     }
+    if allow {
+        println!("Continuing because --allow was given.");
+        return true;
+    }
+    eprintln!(
+        "Commit blocked. Unstage the files, list intended ones in {}, or rerun with --allow.",
+        scan::ALLOWLIST_FILE
+    );
+    false
+}
+
+fn is_excluded(path: &str, patterns: &[Pattern]) -> bool {
+    let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+    patterns.iter().any(|p| p.matches(path) || p.matches(file_name))
+}
+
+/// Asks a yes/no question unless the flags settle it, exiting if there is no terminal to ask on.
+fn ask(question: &str, preset: Option<bool>, flags: &str) -> bool {
+    super::ask(question, preset, flags).unwrap_or_else(|e| exit_with(&e))
+}
+
+/// Exits when a prompt can't be shown, since `odin update` has nothing sensible to fall back on.
+fn require_terminal(question: &str, flags: &str) {
+    super::require_terminal(question, flags).unwrap_or_else(|e| exit_with(&e));
+}
+
+fn exit_with(error: &str) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
+
+fn divergence_name(choice: Divergence) -> &'static str {
+    match choice {
+        Divergence::Rebase => "rebase",
+        Divergence::Merge => "merge",
+        Divergence::Abort => "abort",
+    }
+}
+
+fn push(args: &[&str]) -> bool {
+    println!("Pushing to remote...");
+    let push_status = Command::new("git").args(args).status();
+    if push_status.is_ok_and(|s| s.success()) {
+        println!("Pushed to remote repository.");
+        true
     } else {
-        println!("No remote repository configured. Skipping push.");
+        eprintln!("Failed to push to remote.");
+        false
     }
-}
\ No newline at end of file
+}
+
+/// Explains that the current branch has no upstream and offers to push it with `-u`.
+fn offer_upstream(repo: &Path, options: &Options) {
+    let Some(branch) = git::current_branch(repo) else {
+        println!("HEAD is detached; there is no branch to synchronize.");
+        return;
+    };
+    println!("Branch '{}' has no upstream branch.", branch);
+
+    if let Some(default) = git::default_branch(repo) {
+        let base = format!("origin/{}", default);
+        if default != branch && git::ref_exists(repo, &base) {
+            let ahead = git::count(repo, &format!("{}..HEAD", base)).unwrap_or(0);
+            let behind = git::count(repo, &format!("HEAD..{}", base)).unwrap_or(0);
+            println!("Compared to {}: {} commit{} ahead, {} commit{} behind.", base, ahead, plural(ahead), behind, plural(behind));
+        }
+    }
+
+    let question = format!("Push '{}' to origin and set it as upstream?", branch);
+    if !ask(&question, options.push_answer(), "--yes or --no-push") {
+        println!("Upstream not set.");
+        return;
+    }
+    if push(&["push", "-u", "origin", &branch]) {
+        println!("Upstream set to origin/{}.", branch);
+    }
+}
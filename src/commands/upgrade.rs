@@ -17,7 +17,7 @@ pub fn run() {
 
     // Fetch latest release using gh
     let output = Command::new("gh")
-        .args(&["api", "repos/andremillet/odin/releases/latest"])
+        .args(["api", "repos/andremillet/odin/releases/latest"])
         .output();
 
     let release: Release = match output {
@@ -64,7 +64,7 @@ pub fn run() {
 
     let temp_path = "/tmp/odin_new";
     let download = Command::new("curl")
-        .args(&["-L", "-o", temp_path, &download_url])
+        .args(["-L", "-o", temp_path, &download_url])
         .status();
 
     if !download.map(|s| s.success()).unwrap_or(false) {
@@ -73,7 +73,7 @@ pub fn run() {
     }
 
     // Ensure the downloaded binary has execute permissions
-    let _ = Command::new("chmod").args(&["+x", temp_path]).status();
+    let _ = Command::new("chmod").args(["+x", temp_path]).status();
 
     // Find current binary path
     let which = Command::new("which").arg("odin").output();
@@ -89,7 +89,7 @@ pub fn run() {
 
     // Replace binary (may need sudo)
     let mv = Command::new("sudo")
-        .args(&["mv", temp_path, &binary_path])
+        .args(["mv", temp_path, &binary_path])
         .status();
 
     if mv.map(|s| s.success()).unwrap_or(false) {
@@ -97,18 +97,18 @@ pub fn run() {
         if binary_path.starts_with("/home/") {
             // User directory, restore ownership and permissions
             let _ = Command::new("sudo")
-                .args(&["chown", &std::env::var("USER").unwrap_or_else(|_| "user".to_string()), &binary_path])
+                .args(["chown", &std::env::var("USER").unwrap_or_else(|_| "user".to_string()), &binary_path])
                 .status();
             let _ = Command::new("chmod")
-                .args(&["+x", &binary_path])
+                .args(["+x", &binary_path])
                 .status();
         } else {
             // System directory, use sudo
             let _ = Command::new("sudo")
-                .args(&["chmod", "+x", &binary_path])
+                .args(["chmod", "+x", &binary_path])
                 .status();
             let _ = Command::new("sudo")
-                .args(&["chown", &std::env::var("USER").unwrap_or_else(|_| "user".to_string()), &binary_path])
+                .args(["chown", &std::env::var("USER").unwrap_or_else(|_| "user".to_string()), &binary_path])
                 .status();
         }
         println!("Odin updated to v{} successfully!", latest_version);
@@ -0,0 +1,324 @@
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use regex::Regex;
+use crate::project::CommitConfig;
+
+/// The types from the Conventional Commits specification and its common extensions.
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+fn types(config: &CommitConfig) -> Vec<String> {
+    match &config.types {
+        Some(types) if !types.is_empty() => types.clone(),
+        _ => DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+/// Checks `message` against the project's commit rules, returning every violation.
+pub fn validate(message: &str, config: &CommitConfig) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    let subject = message.lines().next().unwrap_or("").trim();
+
+    if subject.is_empty() {
+        problems.push("Commit message cannot be empty.".to_string());
+    }
+    if let Some(max) = config.max_subject_length {
+        let len = subject.chars().count();
+        if len > max {
+            problems.push(format!("Subject is {} characters long; the limit is {}.", len, max));
+        }
+    }
+    if config.require_issue && !Regex::new(r"(^|[\s(])([\w.-]+/[\w.-]+)?#\d+\b").unwrap().is_match(message) {
+        problems.push("Message must reference an issue, e.g. '#12'.".to_string());
+    }
+    if config.is_conventional() {
+        let header = Regex::new(r"^(?P<type>[a-z]+)(\([^()\s]+\))?!?: \S").unwrap();
+        match header.captures(subject) {
+            Some(caps) => {
+                let kind = &caps["type"];
+                let allowed = types(config);
+                if !allowed.iter().any(|t| t == kind) {
+                    problems.push(format!("Unknown commit type '{}'. Allowed: {}.", kind, allowed.join(", ")));
+                }
+            }
+            None => problems.push("Subject must follow Conventional Commits: 'type(scope): subject'.".to_string()),
+        }
+    }
+
+    if problems.is_empty() { Ok(()) } else { Err(problems) }
+}
+
+/// Asks for type, scope, subject, body and breaking change, and assembles a
+/// Conventional Commits message. The suggestion, if any, pre-fills the answers.
+/// Returns `None` if the user cancels.
+pub fn guided(config: &CommitConfig, suggestion: Option<&Suggestion>) -> Option<String> {
+    let theme = ColorfulTheme::default();
+    let types = types(config);
+
+    let default_kind = suggestion
+        .and_then(|s| types.iter().position(|t| t == s.kind))
+        .unwrap_or(0);
+    let kind = Select::with_theme(&theme)
+        .with_prompt("Type of change")
+        .items(&types)
+        .default(default_kind)
+        .interact_opt()
+        .ok()??;
+    let scope: String = Input::with_theme(&theme)
+        .with_prompt("Scope (optional)")
+        .with_initial_text(suggestion.and_then(|s| s.scope.clone()).unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()
+        .ok()?;
+    let subject: String = Input::with_theme(&theme)
+        .with_prompt("Subject")
+        .with_initial_text(suggestion.map(|s| lowercase_first(&s.subject)).unwrap_or_default())
+        .interact_text()
+        .ok()?;
+    let body: String = Input::with_theme(&theme)
+        .with_prompt("Body (optional)")
+        .allow_empty(true)
+        .interact_text()
+        .ok()?;
+    let breaking = Confirm::with_theme(&theme)
+        .with_prompt("Is this a breaking change?")
+        .default(false)
+        .interact()
+        .ok()?;
+    let breaking_note = if breaking {
+        let note: String = Input::with_theme(&theme)
+            .with_prompt("Describe the breaking change")
+            .interact_text()
+            .ok()?;
+        Some(note)
+    } else {
+        None
+    };
+    let issue: String = if config.require_issue {
+        Input::with_theme(&theme)
+            .with_prompt("Issue reference (e.g. #12)")
+            .interact_text()
+            .ok()?
+    } else {
+        String::new()
+    };
+
+    let mut footers = Vec::new();
+    if let Some(note) = &breaking_note {
+        footers.push(format!("BREAKING CHANGE: {}", note.trim()));
+    }
+    if !issue.trim().is_empty() {
+        footers.push(format!("Refs: {}", issue.trim()));
+    }
+    Some(conventional_message(&types[kind], scope.trim(), breaking, subject.trim(), body.trim(), &footers))
+}
+
+pub fn conventional_message(kind: &str, scope: &str, breaking: bool, subject: &str, body: &str, footers: &[String]) -> String {
+    let mut message = kind.to_string();
+    if !scope.is_empty() {
+        message.push_str(&format!("({})", scope));
+    }
+    if breaking {
+        message.push('!');
+    }
+    message.push_str(": ");
+    message.push_str(subject);
+    if !body.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(body);
+    }
+    if !footers.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&footers.join("\n"));
+    }
+    message
+}
+
+/// Adds a `Fixes #N` footer for each issue the message doesn't already
+/// mention, joining an existing footer block if the message ends with one.
+pub fn with_fixes(message: &str, issues: &[u32]) -> String {
+    let message = message.trim_end();
+    let footers: Vec<String> = issues
+        .iter()
+        .filter(|n| !Regex::new(&format!(r"(?i)\bfixes #{}\b", n)).unwrap().is_match(message))
+        .map(|n| format!("Fixes #{}", n))
+        .collect();
+    if message.is_empty() || footers.is_empty() {
+        return message.to_string();
+    }
+
+    let footer_line = Regex::new(r"^([A-Za-z-]+: |[A-Za-z-]+ #|BREAKING CHANGE: )").unwrap();
+    let (head, last) = message.rsplit_once("\n\n").unwrap_or(("", message));
+    let ends_with_footers = !head.is_empty() && last.lines().all(|line| footer_line.is_match(line));
+    let separator = if ends_with_footers { "\n" } else { "\n\n" };
+    format!("{}{}{}", message, separator, footers.join("\n"))
+}
+
+/// A commit message guessed from the staged changes.
+pub struct Suggestion {
+    pub kind: &'static str,
+    pub scope: Option<String>,
+    pub subject: String,
+}
+
+impl Suggestion {
+    /// The message to pre-fill, in Conventional Commits form if the project uses it.
+    pub fn message(&self, config: &CommitConfig) -> String {
+        if config.is_conventional() {
+            let subject = lowercase_first(&self.subject);
+            conventional_message(self.kind, self.scope.as_deref().unwrap_or(""), false, &subject, "", &[])
+        } else {
+            self.subject.clone()
+        }
+    }
+}
+
+fn lowercase_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Builds a deterministic summary of the staged changes: what happened to which
+/// files, the directory most of them live in, and a scope such as `docs` or `test`.
+pub fn suggest(dir: &std::path::Path) -> Option<Suggestion> {
+    let out = crate::git::output(dir, &["diff", "--cached", "--name-status", "-M"])?;
+    let entries: Vec<(char, &str)> = out
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let status = fields.next()?.chars().next()?;
+            // Renames list the old path first; the new path is what matters
+            let path = fields.next_back()?;
+            Some((status, path))
+        })
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+
+    let paths: Vec<&str> = entries.iter().map(|(_, p)| *p).collect();
+    let category = [
+        ("docs", is_docs as fn(&str) -> bool),
+        ("test", is_test),
+        ("ci", is_ci),
+        ("build", is_build),
+    ]
+    .into_iter()
+    .find(|(_, matches)| paths.iter().all(|p| matches(p)))
+    .map(|(kind, _)| kind);
+    let all_added = entries.iter().all(|(s, _)| *s == 'A');
+    let kind = category.unwrap_or(if all_added { "feat" } else { "chore" });
+
+    let verb = |status: char| match status {
+        'A' => "Add",
+        'D' => "Remove",
+        'R' => "Rename",
+        _ => "Update",
+    };
+
+    if let [(status, path)] = entries.as_slice() {
+        let path = std::path::Path::new(path);
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let scope = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+            .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_lowercase()));
+        return Some(Suggestion { kind, scope: category.map_or(scope, |_| None), subject: format!("{} {}", verb(*status), name) });
+    }
+
+    let directory = dominant_directory(&paths);
+    let location = directory.as_deref().map_or(String::new(), |d| format!(" in {}", d));
+    let scope = directory
+        .as_deref()
+        .and_then(|d| d.rsplit('/').next())
+        .map(str::to_string)
+        .filter(|_| category.is_none());
+
+    let first = entries[0].0;
+    let subject = if entries.iter().all(|(s, _)| *s == first) {
+        format!("{} {} files{}", verb(first), entries.len(), location)
+    } else {
+        let count = |wanted: &[char]| entries.iter().filter(|(s, _)| wanted.contains(s)).count();
+        let parts: Vec<String> = [
+            (count(&['A']), "added"),
+            (count(&['M', 'T']), "modified"),
+            (count(&['D']), "deleted"),
+            (count(&['R', 'C']), "renamed"),
+        ]
+        .into_iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{} {}", n, label))
+        .collect();
+        format!("Update {} files{} ({})", entries.len(), location, parts.join(", "))
+    };
+    Some(Suggestion { kind, scope, subject })
+}
+
+/// The deepest directory shared by all paths, or else the directory holding most of them.
+fn dominant_directory(paths: &[&str]) -> Option<String> {
+    let dirs: Vec<Vec<&str>> = paths
+        .iter()
+        .map(|p| {
+            let mut parts: Vec<&str> = p.split('/').collect();
+            parts.pop();
+            parts
+        })
+        .collect();
+
+    let mut common: Vec<&str> = dirs[0].clone();
+    for dir in &dirs[1..] {
+        let shared = common.iter().zip(dir).take_while(|(a, b)| a == b).count();
+        common.truncate(shared);
+    }
+    if !common.is_empty() {
+        return Some(common.join("/"));
+    }
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for dir in dirs.iter().filter(|d| !d.is_empty()) {
+        let dir = dir.join("/");
+        match counts.iter_mut().find(|(d, _)| *d == dir) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((dir, 1)),
+        }
+    }
+    // Only call a directory dominant if it holds more than half of the files
+    counts
+        .into_iter()
+        .max_by_key(|(_, n)| *n)
+        .filter(|(_, n)| n * 2 > paths.len())
+        .map(|(d, _)| d)
+}
+
+fn is_docs(path: &str) -> bool {
+    path.starts_with("docs/") || path.ends_with(".md") || path.ends_with(".rst")
+}
+
+fn is_test(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || path.contains("__tests__/")
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+fn is_ci(path: &str) -> bool {
+    path.starts_with(".github/workflows/") || path == ".gitlab-ci.yml" || path.starts_with(".circleci/")
+}
+
+fn is_build(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    matches!(
+        name,
+        "Cargo.toml" | "Cargo.lock" | "package.json" | "package-lock.json" | "yarn.lock" | "pnpm-lock.yaml"
+            | "pyproject.toml" | "poetry.lock" | "requirements.txt" | "Makefile" | "Dockerfile" | "build.rs"
+    )
+}
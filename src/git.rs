@@ -0,0 +1,261 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in `dir` and returns trimmed stdout, or `None` if the command fails.
+pub fn output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn remote_url(dir: &Path) -> Option<String> {
+    output(dir, &["remote", "get-url", "origin"]).filter(|url| !url.is_empty())
+}
+
+/// Clones `url` into `dest`, creating parent directories as needed.
+pub fn clone(url: &str, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let status = Command::new("git")
+        .args(["clone", url])
+        .arg(dest)
+        .status()
+        .map_err(|e| format!("Error running git clone: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("git clone of {} failed.", url))
+    }
+}
+
+/// Working tree and branch summary, read from `git status --porcelain=v2 --branch`.
+#[derive(Default, Clone)]
+pub struct Status {
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+}
+
+impl Status {
+    pub fn is_dirty(&self) -> bool {
+        self.staged + self.unstaged + self.untracked + self.conflicted > 0
+    }
+
+    /// One-line description such as `main +2 -1 dirty`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.branch.clone().unwrap_or_else(|| "(detached)".to_string())];
+        if self.upstream.is_none() {
+            parts.push("no upstream".to_string());
+        } else {
+            if self.ahead > 0 {
+                parts.push(format!("+{}", self.ahead));
+            }
+            if self.behind > 0 {
+                parts.push(format!("-{}", self.behind));
+            }
+        }
+        parts.push(if self.is_dirty() { "dirty" } else { "clean" }.to_string());
+        parts.join(" ")
+    }
+}
+
+pub fn status(dir: &Path) -> Option<Status> {
+    let out = output(dir, &["status", "--porcelain=v2", "--branch"])?;
+    let mut status = Status::default();
+    for line in out.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" {
+                status.branch = Some(head.to_string());
+            }
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        } else if line.starts_with("u ") {
+            status.conflicted += 1;
+        } else if let Some(rest) = line.strip_prefix("1 ").or_else(|| line.strip_prefix("2 ")) {
+            let xy = rest.as_bytes();
+            if xy.first().is_some_and(|&c| c != b'.') {
+                status.staged += 1;
+            }
+            if xy.get(1).is_some_and(|&c| c != b'.') {
+                status.unstaged += 1;
+            }
+        }
+    }
+    Some(status)
+}
+
+/// Name of the checked-out branch, or `None` on a detached HEAD.
+pub fn current_branch(dir: &Path) -> Option<String> {
+    output(dir, &["symbolic-ref", "--short", "-q", "HEAD"]).filter(|b| !b.is_empty())
+}
+
+/// The configured upstream of the current branch (`@{u}`), e.g. `origin/main`.
+pub fn upstream(dir: &Path) -> Option<String> {
+    output(dir, &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"]).filter(|u| !u.is_empty())
+}
+
+/// The remote's default branch name, from `origin/HEAD` or, failing that, `gh repo view`.
+pub fn default_branch(dir: &Path) -> Option<String> {
+    if let Some(head) = output(dir, &["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"])
+        && let Some(name) = head.strip_prefix("origin/")
+    {
+        return Some(name.to_string());
+    }
+    let output = Command::new("gh")
+        .args(["repo", "view", "--json", "defaultBranchRef", "-q", ".defaultBranchRef.name"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+pub fn ref_exists(dir: &Path, reference: &str) -> bool {
+    output(dir, &["rev-parse", "--verify", "-q", reference]).is_some()
+}
+
+/// Number of commits in a `rev-list` range such as `HEAD..origin/main`.
+pub fn count(dir: &Path, range: &str) -> Option<usize> {
+    output(dir, &["rev-list", "--count", range])?.parse().ok()
+}
+
+/// Where the current branch stands relative to its upstream.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SyncState {
+    UpToDate,
+    Ahead(usize),
+    Behind(usize),
+    Diverged { ahead: usize, behind: usize },
+    NoUpstream,
+}
+
+/// Compares `HEAD` with `@{u}`. Returns `None` if the upstream ref cannot be resolved.
+pub fn sync_state(dir: &Path) -> Option<SyncState> {
+    let Some(upstream) = upstream(dir) else {
+        return Some(SyncState::NoUpstream);
+    };
+    let counts = output(dir, &["rev-list", "--left-right", "--count", &format!("HEAD...{}", upstream)])?;
+    let mut parts = counts.split_whitespace().map(|n| n.parse::<usize>().ok());
+    let (Some(Some(ahead)), Some(Some(behind))) = (parts.next(), parts.next()) else {
+        return None;
+    };
+    Some(match (ahead, behind) {
+        (0, 0) => SyncState::UpToDate,
+        (ahead, 0) => SyncState::Ahead(ahead),
+        (0, behind) => SyncState::Behind(behind),
+        (ahead, behind) => SyncState::Diverged { ahead, behind },
+    })
+}
+
+/// Paths with unresolved merge conflicts.
+pub fn conflicted_files(dir: &Path) -> Vec<String> {
+    output(dir, &["diff", "--name-only", "--diff-filter=U"])
+        .map(|out| out.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Every staged path, with both sides of a rename.
+pub fn staged_files(dir: &Path) -> Vec<String> {
+    output(dir, &["diff", "--cached", "--name-only", "--no-renames", "-z"])
+        .map(|out| out.split('\0').filter(|p| !p.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// One entry of `git status --porcelain`.
+pub struct Change {
+    /// The two-letter status code, e.g. ` M`, `A `, `??`.
+    pub code: String,
+    pub path: String,
+    /// The original path of a rename or copy.
+    pub orig: Option<String>,
+}
+
+impl Change {
+    pub fn is_untracked(&self) -> bool {
+        self.code == "??"
+    }
+
+    /// The path plus, for renames, the original path; both are needed to stage it.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.path.as_str()).chain(self.orig.as_deref())
+    }
+}
+
+/// Lists changed files, optionally limited to the given pathspecs.
+pub fn changes(dir: &Path, pathspecs: &[String]) -> Option<Vec<Change>> {
+    let mut args = vec!["status", "--porcelain", "-z", "--untracked-files=all", "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = Command::new("git").args(&args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split('\0').filter(|f| !f.is_empty());
+    let mut changes = Vec::new();
+    while let Some(entry) = fields.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        let code = entry[..2].to_string();
+        let path = entry[3..].to_string();
+        let orig = if code.contains('R') || code.contains('C') {
+            fields.next().map(str::to_string)
+        } else {
+            None
+        };
+        changes.push(Change { code, path, orig });
+    }
+    Some(changes)
+}
+
+pub fn has_staged_changes(dir: &Path) -> bool {
+    Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(dir)
+        .status()
+        .is_ok_and(|s| s.code() == Some(1))
+}
+
+/// Stashes tracked changes and returns the new stash commit, or `None` if
+/// there was nothing to stash or git failed.
+pub fn stash_push(dir: &Path, message: &str) -> Option<String> {
+    let before = output(dir, &["rev-parse", "-q", "--verify", "refs/stash"]);
+    output(dir, &["stash", "push", "-q", "-m", message])?;
+    let after = output(dir, &["rev-parse", "-q", "--verify", "refs/stash"])?;
+    (before.as_deref() != Some(after.as_str())).then_some(after)
+}
+
+/// The `stash@{n}` name of the stash commit `sha`, if it is still in the stash list.
+pub fn stash_ref(dir: &Path, sha: &str) -> Option<String> {
+    let list = output(dir, &["stash", "list", "--format=%H"])?;
+    let index = list.lines().position(|line| line == sha)?;
+    Some(format!("stash@{{{}}}", index))
+}
+
+/// True while a rebase or merge is stopped waiting for the user.
+pub fn operation_in_progress(dir: &Path) -> bool {
+    ["rebase-merge", "rebase-apply", "MERGE_HEAD"].iter().any(|name| {
+        output(dir, &["rev-parse", "--git-path", name]).is_some_and(|path| dir.join(path).exists())
+    })
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `gh` in `dir` and returns trimmed stdout, or `None` if the command fails.
+pub fn output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("gh").args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Login of the authenticated GitHub user.
+pub fn current_user() -> Option<String> {
+    output(Path::new("."), &["api", "user", "-q", ".login"]).filter(|login| !login.is_empty())
+}
+
+/// What `odin update` shows about a repository before touching it.
+pub struct RepoInfo {
+    /// `owner/name`.
+    pub full_name: String,
+    pub owner: String,
+    pub stars: u64,
+    /// Commits on the default branch; `None` if the repository is empty.
+    pub commits: Option<u64>,
+}
+
+/// Looks up `repo` (`owner/name`, or a bare name for the current user).
+/// Returns `None` if it doesn't exist or can't be read.
+pub fn repo_info(repo: &str) -> Option<RepoInfo> {
+    let json = output(Path::new("."), &["repo", "view", repo, "--json", "nameWithOwner,stargazerCount"])?;
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    let full_name = value["nameWithOwner"].as_str()?.to_string();
+    let (owner, name) = full_name.split_once('/')?;
+    let query = format!(
+        "query {{ repository(owner: \"{}\", name: \"{}\") {{ defaultBranchRef {{ target {{ ... on Commit {{ history {{ totalCount }} }} }} }} }} }}",
+        owner, name
+    );
+    let commits = output(
+        Path::new("."),
+        &["api", "graphql", "-f", &format!("query={}", query), "-q", ".data.repository.defaultBranchRef.target.history.totalCount"],
+    )
+    .and_then(|n| n.parse().ok());
+    Some(RepoInfo {
+        owner: owner.to_string(),
+        stars: value["stargazerCount"].as_u64().unwrap_or(0),
+        commits,
+        full_name,
+    })
+}
+
+pub fn repo_exists(repo: &str) -> bool {
+    output(Path::new("."), &["repo", "view", repo, "--json", "name"]).is_some()
+}
+
+/// The URL `git` should use for `owner/name`, following gh's `git_protocol` setting.
+pub fn clone_url(full_name: &str) -> String {
+    if output(Path::new("."), &["config", "get", "git_protocol"]).as_deref() == Some("ssh") {
+        format!("git@github.com:{}.git", full_name)
+    } else {
+        format!("https://github.com/{}.git", full_name)
+    }
+}
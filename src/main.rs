@@ -1,6 +1,23 @@
 use clap::{Parser, Subcommand};
 
 mod commands;
+mod commit;
+mod git;
+mod github;
+mod policy;
+mod project;
+mod registry;
+mod scan;
+mod settings;
+
+use commands::branch::BranchAction;
+use commands::ci::CiAction;
+use commands::group::GroupAction;
+use commands::issue::IssueAction;
+use commands::policy::PolicyAction;
+use commands::pr::PrAction;
+use commands::remote::RemoteAction;
+use commands::tag::TagAction;
 
 #[derive(Parser)]
 #[command(name = "odin")]
@@ -21,11 +38,207 @@ enum Commands {
         name: String,
     },
     /// List all projects and select one to work on
-    List,
+    List {
+        /// Only show projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only show projects in this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Open a project in an editor, on GitHub or in the file manager
+    #[command(group(clap::ArgGroup::new("target").args(["editor", "web", "files"])))]
+    Open {
+        /// Name of the project (defaults to the project in the current directory)
+        name: Option<String>,
+        /// Open in $VISUAL/$EDITOR or the editor set in ~/.odin/config.toml (default)
+        #[arg(long)]
+        editor: bool,
+        /// Open the GitHub repository in the browser
+        #[arg(long)]
+        web: bool,
+        /// Open the project directory in the file manager
+        #[arg(long)]
+        files: bool,
+    },
     /// Update project: add, commit, and push changes
-    Update,
+    Update {
+        /// Only stage changes under these paths
+        paths: Vec<String>,
+        /// Stage all changes without asking (the old `git add .` behaviour)
+        #[arg(long)]
+        all: bool,
+        /// Commit even if the secret and large-file scan finds something
+        #[arg(long)]
+        allow: bool,
+        /// Commit message (skips the prompt)
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Build the commit message step by step following Conventional Commits
+        #[arg(long, conflicts_with = "message")]
+        conventional: bool,
+        /// Write the commit message in $EDITOR, starting from the suggested one
+        #[arg(short, long, conflicts_with_all = ["message", "conventional"])]
+        edit: bool,
+        /// Answer yes to every question; uses the suggested message if -m is not given
+        #[arg(short, long)]
+        yes: bool,
+        /// Commit locally but never push
+        #[arg(long)]
+        no_push: bool,
+        /// Pull when the remote has new commits
+        #[arg(long)]
+        pull: bool,
+        /// Do not offer to adopt a repository that is not an Odin project
+        #[arg(long)]
+        no_adopt: bool,
+        /// Add a "Fixes #N" footer to the commit message (repeatable)
+        #[arg(long, value_name = "ISSUE")]
+        fixes: Vec<u32>,
+    },
     /// Upgrade Odin: check for and install updates
     Upgrade,
+    /// Full-screen dashboard of all registered projects
+    Dash {
+        /// Only show projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only show projects in this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Check the environment and the project registry for problems
+    Doctor {
+        /// Only check the project registry
+        #[arg(long)]
+        registry: bool,
+        /// Remove projects whose directory is missing from the registry
+        #[arg(long)]
+        prune: bool,
+        /// Re-clone missing projects from their recorded remote
+        #[arg(long)]
+        reclone: bool,
+    },
+    /// Run a command in every registered project directory
+    #[command(visible_alias = "exec")]
+    Foreach {
+        /// Only run in projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only run in projects in this group
+        #[arg(long)]
+        group: Option<String>,
+        /// Number of projects to run at the same time
+        #[arg(long, default_value_t = 1)]
+        parallel: usize,
+        /// Stop starting new projects after the first failure
+        #[arg(long)]
+        fail_fast: bool,
+        /// Only run in projects with uncommitted changes
+        #[arg(long)]
+        only_dirty: bool,
+        /// Command to run, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Show the state of the current project without changing anything
+    Status {
+        /// Show every project with this tag instead of the current one
+        #[arg(long)]
+        tag: Option<String>,
+        /// Show every project in this group instead of the current one
+        #[arg(long)]
+        group: Option<String>,
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fetch every project, fast-forward the ones behind and push the ones ahead
+    #[command(group(clap::ArgGroup::new("direction").args(["pull", "push", "both"])))]
+    Sync {
+        /// Only sync projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only sync projects in this group
+        #[arg(long)]
+        group: Option<String>,
+        /// Only fast-forward projects that are behind
+        #[arg(long)]
+        pull: bool,
+        /// Only push projects that are ahead
+        #[arg(long)]
+        push: bool,
+        /// Pull and push (default)
+        #[arg(long)]
+        both: bool,
+        /// Number of projects to sync at the same time
+        #[arg(long, default_value_t = 8)]
+        parallel: usize,
+    },
+    /// Create, switch and finish branches
+    Branch {
+        #[command(subcommand)]
+        action: BranchAction,
+    },
+    /// Create, list, check out and merge pull requests
+    Pr {
+        #[command(subcommand)]
+        action: PrAction,
+    },
+    /// List, open and close GitHub issues
+    Issue {
+        #[command(subcommand)]
+        action: IssueAction,
+    },
+    /// Apply the shared label and branch protection policy
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
+    /// Manage the project's GitHub repository settings
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+    /// Set up GitHub Actions and show workflow results
+    Ci {
+        #[command(subcommand)]
+        action: CiAction,
+    },
+    /// Show the changes since the last release, grouped by commit type
+    Changelog {
+        /// Tag or commit to start from (defaults to the latest version tag)
+        #[arg(long, value_name = "TAG")]
+        since: Option<String>,
+        /// Prepend the changes to CHANGELOG.md
+        #[arg(long)]
+        write: bool,
+    },
+    /// Bump the version, tag it and publish a GitHub release
+    Release {
+        /// major, minor, patch or an explicit version such as 2.0.0
+        #[arg(default_value = "patch")]
+        bump: String,
+        /// Create the GitHub release as a draft
+        #[arg(long)]
+        draft: bool,
+        /// File to attach to the release (repeatable)
+        #[arg(long = "asset", value_name = "FILE")]
+        assets: Vec<String>,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage project tags
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    /// Manage project groups
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
 }
 
 fn main() {
@@ -38,14 +251,91 @@ fn main() {
         Commands::Create { name } => {
             commands::create::run(&name);
         }
-        Commands::List => {
-            commands::list::run();
+        Commands::List { tag, group } => {
+            commands::list::run(&registry::Filter::new(tag, group));
+        }
+        Commands::Open { name, editor: _, web, files } => {
+            let target = if web {
+                commands::open::Target::Web
+            } else if files {
+                commands::open::Target::Files
+            } else {
+                commands::open::Target::Editor
+            };
+            commands::open::run(name.as_deref(), target);
         }
-        Commands::Update => {
-            commands::update::run();
+        Commands::Update { paths, all, allow, message, conventional, edit, yes, no_push, pull, no_adopt, fixes } => {
+            let options = commands::update::Options {
+                all,
+                paths,
+                allow,
+                message,
+                conventional,
+                edit,
+                yes,
+                no_push,
+                pull,
+                no_adopt,
+                fixes,
+            };
+            commands::update::run(&options);
         }
         Commands::Upgrade => {
             commands::upgrade::run();
         }
+        Commands::Dash { tag, group } => {
+            commands::dash::run(&registry::Filter::new(tag, group));
+        }
+        Commands::Doctor { registry, prune, reclone } => {
+            commands::doctor::run(registry, prune, reclone);
+        }
+        Commands::Foreach { tag, group, parallel, fail_fast, only_dirty, command } => {
+            let options = commands::foreach::Options { parallel, fail_fast, only_dirty };
+            commands::foreach::run(&registry::Filter::new(tag, group), &options, &command);
+        }
+        Commands::Status { tag, group, json } => {
+            commands::status::run(&registry::Filter::new(tag, group), json);
+        }
+        Commands::Sync { tag, group, pull, push, both: _, parallel } => {
+            let direction = if pull {
+                commands::sync::Direction::Pull
+            } else if push {
+                commands::sync::Direction::Push
+            } else {
+                commands::sync::Direction::Both
+            };
+            let options = commands::sync::Options { direction, parallel };
+            commands::sync::run(&registry::Filter::new(tag, group), &options);
+        }
+        Commands::Branch { action } => {
+            commands::branch::run(&action);
+        }
+        Commands::Pr { action } => {
+            commands::pr::run(&action);
+        }
+        Commands::Issue { action } => {
+            commands::issue::run(&action);
+        }
+        Commands::Policy { action } => {
+            commands::policy::run(&action);
+        }
+        Commands::Remote { action } => {
+            commands::remote::run(&action);
+        }
+        Commands::Ci { action } => {
+            commands::ci::run(&action);
+        }
+        Commands::Changelog { since, write } => {
+            commands::changelog::run(since.as_deref(), write);
+        }
+        Commands::Release { bump, draft, assets, yes } => {
+            commands::release::run(&commands::release::Options { bump, draft, assets, yes });
+        }
+        Commands::Tag { action } => {
+            commands::tag::run(&action);
+        }
+        Commands::Group { action } => {
+            commands::group::run(&action);
+        }
     }
 }
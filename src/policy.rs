@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use crate::registry;
+
+/// Labels and branch protection every repository should have, read from the
+/// project's `POLICY.toml` or, failing that, `~/.odin/policy.toml`.
+#[derive(Deserialize, Default)]
+pub struct Policy {
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// Rules for the default branch; left alone if absent.
+    pub protection: Option<Protection>,
+}
+
+#[derive(Deserialize)]
+pub struct Label {
+    pub name: String,
+    /// Hex color without `#`, e.g. `"d73a4a"`.
+    pub color: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Branch protection settings. Settings that are left out keep their current value.
+#[derive(Deserialize, Default)]
+pub struct Protection {
+    /// Approving reviews a pull request needs before it can be merged.
+    pub required_reviews: Option<u64>,
+    /// Status checks that must pass, e.g. `["ci"]`.
+    pub required_checks: Option<Vec<String>>,
+    /// Require branches to be up to date with the base before merging.
+    pub strict: Option<bool>,
+    /// Apply the rules to administrators too.
+    pub enforce_admins: Option<bool>,
+    pub allow_force_pushes: Option<bool>,
+    pub allow_deletions: Option<bool>,
+}
+
+pub fn global_file() -> PathBuf {
+    registry::odin_dir().join("policy.toml")
+}
+
+/// Loads the policy for the project in `dir` and returns it with the file it came from.
+/// Returns `Ok(None)` if neither file exists.
+pub fn load(dir: &Path) -> Result<Option<(Policy, PathBuf)>, String> {
+    let path = [dir.join("POLICY.toml"), global_file()].into_iter().find(|p| p.is_file());
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let policy = toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    Ok(Some((policy, path)))
+}
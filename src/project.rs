@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+
+/// Per-project settings, read from the project's `CONFIG.toml`.
+#[derive(Deserialize, Default)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub app: App,
+    #[serde(default)]
+    pub update: UpdateConfig,
+    #[serde(default)]
+    pub commit: CommitConfig,
+    #[serde(default)]
+    pub branch: BranchConfig,
+    #[serde(default)]
+    pub pr: PrConfig,
+    #[serde(default)]
+    pub github: GithubConfig,
+}
+
+#[derive(Deserialize, Default)]
+pub struct App {
+    pub name: Option<String>,
+}
+
+/// The `[update]` section, controlling `odin update`.
+#[derive(Deserialize, Default)]
+pub struct UpdateConfig {
+    /// What to do when local and remote have diverged, used as the default answer.
+    pub divergence: Option<Divergence>,
+    /// Glob patterns for files that are never staged, e.g. `["target/**", "*.log"]`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Largest file allowed in a commit before the scanner flags it, e.g. `"10MB"`.
+    pub max_file_size: Option<String>,
+}
+
+/// The `[commit]` section: the commit message format enforced by `odin update`.
+#[derive(Deserialize, Default)]
+pub struct CommitConfig {
+    /// `"conventional"` to require Conventional Commits and prompt for its parts.
+    pub format: Option<String>,
+    /// Longest allowed first line.
+    pub max_subject_length: Option<usize>,
+    /// Require an issue reference such as `#12` somewhere in the message.
+    #[serde(default)]
+    pub require_issue: bool,
+    /// Allowed Conventional Commit types, replacing the standard list.
+    pub types: Option<Vec<String>>,
+}
+
+impl CommitConfig {
+    pub fn is_conventional(&self) -> bool {
+        self.format.as_deref() == Some("conventional")
+    }
+}
+
+/// The `[branch]` section: naming convention and workflow for `odin branch`.
+#[derive(Deserialize, Default)]
+pub struct BranchConfig {
+    /// Allowed prefixes; branch names must look like `<prefix>/<name>`.
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    /// Prefix added by `odin branch new` when the name has none, e.g. `"feature"`.
+    pub default_prefix: Option<String>,
+    /// How `odin branch finish` integrates a branch.
+    pub finish: Option<Finish>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Finish {
+    /// Open a pull request.
+    Pr,
+    /// Merge into the default branch locally and push.
+    Merge,
+}
+
+/// The `[pr]` section, used by `odin pr`.
+#[derive(Deserialize, Default)]
+pub struct PrConfig {
+    /// How `odin pr merge` merges when no strategy flag is given.
+    pub merge: Option<MergeStrategy>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+/// The `[github]` section: repository settings applied by `odin remote sync`.
+/// Settings that are left out are not touched.
+#[derive(Deserialize, Default)]
+pub struct GithubConfig {
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub topics: Option<Vec<String>>,
+    pub visibility: Option<Visibility>,
+    /// Whether the Issues tab is enabled.
+    pub issues: Option<bool>,
+    /// Whether the Wiki tab is enabled.
+    pub wiki: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Private,
+    Internal,
+}
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Divergence {
+    Rebase,
+    Merge,
+    Abort,
+}
+
+impl ProjectConfig {
+    pub fn name(&self) -> &str {
+        self.app.name.as_deref().unwrap_or("Unknown")
+    }
+}
+
+/// Loads `CONFIG.toml` from `dir`. A missing file yields the defaults; an invalid one is reported and ignored.
+pub fn load(dir: &Path) -> ProjectConfig {
+    let path = dir.join("CONFIG.toml");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return ProjectConfig::default();
+    };
+    toml::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Ignoring invalid {}: {}", path.display(), e);
+        ProjectConfig::default()
+    })
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub name: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// URL of the `origin` remote, recorded so a lost checkout can be re-cloned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+impl Project {
+    pub fn new(name: &str, path: &str) -> Self {
+        Project {
+            name: name.to_string(),
+            path: path.to_string(),
+            tags: Vec::new(),
+            group: None,
+            remote: None,
+        }
+    }
+
+    pub fn with_remote(mut self, remote: Option<String>) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    pub fn is_missing(&self) -> bool {
+        !Path::new(&self.path).exists()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn in_group(&self, group: &str) -> bool {
+        self.group.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(group))
+    }
+}
+
+/// Narrows the registry down to the projects matching a tag and/or group.
+#[derive(Default)]
+pub struct Filter {
+    pub tag: Option<String>,
+    pub group: Option<String>,
+}
+
+impl Filter {
+    pub fn new(tag: Option<String>, group: Option<String>) -> Self {
+        Filter { tag, group }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_none() && self.group.is_none()
+    }
+
+    pub fn matches(&self, project: &Project) -> bool {
+        let tag_ok = self.tag.as_deref().is_none_or(|t| project.has_tag(t));
+        let group_ok = self.group.as_deref().is_none_or(|g| project.in_group(g));
+        tag_ok && group_ok
+    }
+
+    pub fn apply(&self, projects: Vec<Project>) -> Vec<Project> {
+        projects.into_iter().filter(|p| self.matches(p)).collect()
+    }
+}
+
+pub fn odin_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".odin")
+}
+
+pub fn projects_file() -> PathBuf {
+    odin_dir().join("projects.json")
+}
+
+pub fn exists() -> bool {
+    projects_file().exists()
+}
+
+/// Loads the project list, returning an empty list if the file is missing or unreadable.
+pub fn load() -> Vec<Project> {
+    let projects_file = projects_file();
+    if !projects_file.exists() {
+        return Vec::new();
+    }
+    let data = fs::read_to_string(&projects_file).unwrap_or_else(|_| "[]".to_string());
+    serde_json::from_str(&data).unwrap_or_default()
+}
+
+pub fn save(projects: &[Project]) -> Result<(), String> {
+    let odin_dir = odin_dir();
+    fs::create_dir_all(&odin_dir).map_err(|e| format!("Failed to create ~/.odin: {}", e))?;
+    let json = serde_json::to_string_pretty(projects).unwrap();
+    fs::write(projects_file(), json).map_err(|e| format!("Failed to update projects.json: {}", e))
+}
+
+/// Appends a project to the registry and saves it.
+pub fn add(project: Project) -> Result<(), String> {
+    let mut projects = load();
+    projects.push(project);
+    save(&projects)
+}
+
+pub fn find_by_name<'a>(projects: &'a mut [Project], name: &str) -> Option<&'a mut Project> {
+    projects.iter_mut().find(|p| p.name == name)
+}
+
+/// Finds the registered project whose directory is `path`, comparing canonical paths.
+pub fn find_by_path<'a>(projects: &'a [Project], path: &Path) -> Option<&'a Project> {
+    let target = path.canonicalize().ok()?;
+    projects
+        .iter()
+        .find(|p| Path::new(&p.path).canonicalize().ok().as_ref() == Some(&target))
+}
+
+/// Stores the remote URL for the project at `path` if it differs from the recorded one.
+pub fn record_remote(path: &Path, remote: &str) -> Result<(), String> {
+    let mut projects = load();
+    let Some(target) = path.canonicalize().ok() else {
+        return Ok(());
+    };
+    let entry = projects
+        .iter_mut()
+        .find(|p| Path::new(&p.path).canonicalize().ok().as_ref() == Some(&target));
+    match entry {
+        Some(project) if project.remote.as_deref() != Some(remote) => {
+            project.remote = Some(remote.to_string());
+            save(&projects)
+        }
+        _ => Ok(()),
+    }
+}
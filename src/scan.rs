@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::Path;
+use glob::Pattern;
+use regex::Regex;
+use crate::git;
+
+/// Staged files larger than this are flagged unless `[update] max_file_size` says otherwise.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Project file listing glob patterns of paths the scanner should skip.
+pub const ALLOWLIST_FILE: &str = ".odinallow";
+
+pub struct Finding {
+    pub path: String,
+    pub line: Option<usize>,
+    pub reason: String,
+}
+
+/// Content patterns that usually mean a credential is being committed.
+const SECRET_PATTERNS: &[(&str, &str)] = &[
+    ("AWS access key ID", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("AWS secret access key", r#"(?i)aws_secret_access_key\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}"#),
+    ("GitHub token", r"\b(ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{36}\b"),
+    ("GitHub fine-grained token", r"\bgithub_pat_[A-Za-z0-9_]{22,}\b"),
+    ("private key", r"-----BEGIN ([A-Z]+ )?PRIVATE KEY-----"),
+];
+
+/// Scans what is currently staged in `dir`: file names, sizes and added lines.
+pub fn staged(dir: &Path, max_file_size: u64) -> Vec<Finding> {
+    let allow = allowlist(dir);
+    let is_allowed = |path: &str| allow.iter().any(|p| p.matches(path));
+    let mut findings = Vec::new();
+
+    let files = git::output(dir, &["diff", "--cached", "--name-only", "--diff-filter=ACMR"]).unwrap_or_default();
+    for path in files.lines().filter(|p| !is_allowed(p)) {
+        if let Some(reason) = sensitive_name(path) {
+            findings.push(Finding { path: path.to_string(), line: None, reason });
+        }
+        let size = git::output(dir, &["cat-file", "-s", &format!(":{}", path)]).and_then(|s| s.parse::<u64>().ok());
+        if let Some(size) = size.filter(|&s| s > max_file_size) {
+            findings.push(Finding {
+                path: path.to_string(),
+                line: None,
+                reason: format!("file is {} (limit {})", format_size(size), format_size(max_file_size)),
+            });
+        }
+    }
+
+    let patterns: Vec<(&str, Regex)> = SECRET_PATTERNS
+        .iter()
+        .map(|(name, pattern)| (*name, Regex::new(pattern).unwrap()))
+        .collect();
+    let diff = git::output(dir, &["diff", "--cached", "-U0", "--no-color", "--diff-filter=ACMR"]).unwrap_or_default();
+    let mut current: Option<&str> = None;
+    let mut in_header = false;
+    let mut line_no = 0;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            in_header = true;
+            current = None;
+        } else if in_header && let Some(path) = line.strip_prefix("+++ ") {
+            current = path.strip_prefix("b/").filter(|p| !is_allowed(p));
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            in_header = false;
+            // "@@ -a,b +c,d @@": added lines start at c
+            line_no = hunk
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+        } else if !in_header && let Some(added) = line.strip_prefix('+') {
+            if let Some(path) = current {
+                for (name, regex) in &patterns {
+                    if regex.is_match(added) {
+                        findings.push(Finding {
+                            path: path.to_string(),
+                            line: Some(line_no),
+                            reason: format!("possible {}", name),
+                        });
+                    }
+                }
+            }
+            line_no += 1;
+        }
+    }
+    findings
+}
+
+fn sensitive_name(path: &str) -> Option<String> {
+    let name = Path::new(path).file_name()?.to_str()?;
+    let is_env = name == ".env"
+        || (name.starts_with(".env.") && !matches!(name, ".env.example" | ".env.sample" | ".env.template"))
+        || name.ends_with(".env");
+    if is_env {
+        return Some("environment file, usually holds secrets".to_string());
+    }
+    let is_key = matches!(name, "id_rsa" | "id_dsa" | "id_ecdsa" | "id_ed25519")
+        || [".pem", ".key", ".p12", ".pfx"].iter().any(|ext| name.ends_with(ext));
+    is_key.then(|| "private key or certificate file".to_string())
+}
+
+fn allowlist(dir: &Path) -> Vec<Pattern> {
+    let Ok(content) = fs::read_to_string(dir.join(ALLOWLIST_FILE)) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| Pattern::new(l).ok())
+        .collect()
+}
+
+/// Parses sizes such as `500KB`, `10MB` or a plain byte count.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_uppercase();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value.as_str(), ""),
+    };
+    let multiplier = match unit.trim() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.parse::<u64>().ok().map(|n| n * multiplier)
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
@@ -0,0 +1,21 @@
+use std::fs;
+use serde::Deserialize;
+use crate::registry;
+
+/// Global Odin settings, read from `~/.odin/config.toml`.
+#[derive(Deserialize, Default)]
+pub struct Settings {
+    /// Command used by `odin open --editor`, e.g. `"code -n"`. Overrides `$VISUAL`/`$EDITOR`.
+    pub editor: Option<String>,
+}
+
+pub fn load() -> Settings {
+    let path = registry::odin_dir().join("config.toml");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    toml::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Ignoring invalid {}: {}", path.display(), e);
+        Settings::default()
+    })
+}
@@ -0,0 +1,91 @@
+//! Drives `odin branch` against a local bare remote.
+
+mod common;
+
+use std::fs;
+use common::{commit, git, Fixture};
+
+/// Commits and pushes a `[branch]` convention so the working copy stays in sync.
+fn with_convention(fx: &Fixture) {
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    let branch = "[branch]\nprefixes = [\"feature\", \"fix\"]\ndefault_prefix = \"feature\"\n";
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n{}", config, branch)).unwrap();
+    git(&fx.work, &["commit", "-q", "-am", "Branch convention"]);
+    git(&fx.work, &["push", "-q"]);
+}
+
+#[test]
+fn new_branches_off_the_updated_default_branch() {
+    let fx = Fixture::new();
+    with_convention(&fx);
+    let other = fx.other_clone();
+    commit(&other, "c.txt");
+    git(&other, &["push", "-q"]);
+
+    let out = fx.odin(&["branch", "new", "login"]);
+    assert!(out.contains("Creating 'feature/login' from origin/main"), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "HEAD"]), "feature/login");
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), fx.remote_head("main"));
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "@{u}"]), "origin/feature/login");
+    assert_eq!(fx.remote_head("feature/login"), fx.remote_head("main"));
+}
+
+#[test]
+fn names_must_follow_the_convention() {
+    let fx = Fixture::new();
+    with_convention(&fx);
+
+    let out = fx.odin(&["branch", "new", "wip/login"]);
+    assert!(out.contains("does not follow the naming convention: use <prefix>/<name> with one of: feature, fix."), "{}", out);
+    assert_eq!(git(&fx.work, &["branch", "--list", "wip/*"]), "");
+}
+
+#[test]
+fn switch_goes_straight_to_a_unique_match() {
+    let fx = Fixture::new();
+    git(&fx.work, &["branch", "feature/search"]);
+    git(&fx.work, &["branch", "fix/crash"]);
+
+    let out = fx.odin(&["branch", "switch", "crash"]);
+    assert!(out.contains("Switched to 'fix/crash'."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "HEAD"]), "fix/crash");
+
+    let out = fx.odin(&["branch", "switch", "a"]);
+    assert!(out.contains("Pass a name that matches exactly one branch."), "{}", out);
+}
+
+#[test]
+fn finish_merge_merges_back_and_deletes_the_branch() {
+    let fx = Fixture::new();
+    fx.odin(&["branch", "new", "feature/report"]);
+    commit(&fx.work, "report.txt");
+
+    let out = fx.odin(&["branch", "finish", "--merge"]);
+    assert!(out.contains("Deleted local branch 'feature/report'."), "{}", out);
+    assert!(out.contains("Deleted remote branch 'origin/feature/report'."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "HEAD"]), "main");
+    assert_eq!(fx.remote_head("main"), git(&fx.work, &["rev-parse", "HEAD"]));
+    assert_eq!(git(&fx.remote, &["ls-tree", "--name-only", "main", "report.txt"]), "report.txt");
+    assert_eq!(git(&fx.remote, &["branch", "--list", "feature/*"]), "");
+}
+
+#[test]
+fn finish_after_a_merged_pr_keeps_commits_that_were_not_merged() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fx.odin(&["branch", "new", "feature/report"]);
+    commit(&fx.work, "report.txt");
+    git(&fx.work, &["push", "-q"]);
+    let merged = git(&fx.work, &["rev-parse", "HEAD"]);
+    fx.gh_reply("pr view", &format!("MERGED {} https://github.com/me/work/pull/3", merged));
+    commit(&fx.work, "late.txt");
+
+    let out = fx.odin_with_env(&["branch", "finish", "--pr"], &[("PATH", &path)], "");
+    assert!(out.contains("'feature/report' has 1 commit that the merged pull request doesn't, so it was kept."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "HEAD"]), "feature/report");
+
+    git(&fx.work, &["reset", "-q", "--hard", "HEAD~1"]);
+    let out = fx.odin_with_env(&["branch", "finish", "--pr"], &[("PATH", &path)], "");
+    assert!(out.contains("Deleted local branch 'feature/report'."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "HEAD"]), "main");
+}
@@ -0,0 +1,112 @@
+//! Runs `odin changelog` against commits following Conventional Commits.
+
+mod common;
+
+use std::fs;
+use common::{git, Fixture};
+
+fn commit_message(fx: &Fixture, file: &str, message: &str) {
+    fs::write(fx.work.join(file), message).unwrap();
+    git(&fx.work, &["add", file]);
+    git(&fx.work, &["commit", "-q", "-m", message]);
+}
+
+fn hash(fx: &Fixture) -> String {
+    git(&fx.work, &["rev-parse", "--short", "HEAD"])
+}
+
+#[test]
+fn groups_commits_since_the_last_tag() {
+    let fx = Fixture::new();
+    commit_message(&fx, "old.txt", "feat: before the release");
+    git(&fx.work, &["tag", "-a", "v1.0.0", "-m", "v1.0.0"]);
+    commit_message(&fx, "a.txt", "feat(cli): add the sync command");
+    let feat = hash(&fx);
+    commit_message(&fx, "b.txt", "fix: handle empty input");
+    let fix = hash(&fx);
+    commit_message(&fx, "c.txt", "refactor!: drop the v1 config format\n\nBREAKING CHANGE: CONFIG.toml must use [commit]");
+    let breaking = hash(&fx);
+    commit_message(&fx, "d.txt", "Tidy up");
+    let other = hash(&fx);
+
+    let out = fx.odin(&["changelog"]);
+    assert_eq!(
+        out.trim(),
+        format!(
+            "## Unreleased\n\n### Breaking Changes\n\n- CONFIG.toml must use [commit] ({})\n\n### Features\n\n- **cli:** add the sync command ({})\n\n### Bug Fixes\n\n- handle empty input ({})\n\n### Other Changes\n\n- Tidy up ({})",
+            breaking, feat, fix, other
+        )
+    );
+
+    let out = fx.odin(&["changelog", "--since", "v0.9.0"]);
+    assert!(out.contains("'v0.9.0' is not a tag or commit"), "{}", out);
+}
+
+#[test]
+fn write_prepends_to_the_changelog() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("CHANGELOG.md"), "# Changelog\n\n## v1.0.0\n\n- First release\n").unwrap();
+    commit_message(&fx, "a.txt", "feat: add export");
+
+    let out = fx.odin(&["changelog", "--write"]);
+    assert!(out.contains("Added the changes since the first commit to CHANGELOG.md."), "{}", out);
+    let changelog = fs::read_to_string(fx.work.join("CHANGELOG.md")).unwrap();
+    assert!(changelog.starts_with("# Changelog\n\n## Unreleased\n\n### Features\n\n- add export ("), "{}", changelog);
+    assert!(changelog.ends_with(")\n\n## v1.0.0\n\n- First release\n"), "{}", changelog);
+}
+
+#[test]
+fn write_goes_above_a_leading_section() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("CHANGELOG.md"), "## v1.0.0\n\n- First release\n").unwrap();
+    commit_message(&fx, "a.txt", "feat: add export");
+
+    fx.odin(&["changelog", "--write"]);
+    let changelog = fs::read_to_string(fx.work.join("CHANGELOG.md")).unwrap();
+    assert!(changelog.starts_with("# Changelog\n\n## Unreleased\n\n### Features\n\n- add export ("), "{}", changelog);
+    assert!(changelog.ends_with(")\n\n## v1.0.0\n\n- First release\n"), "{}", changelog);
+}
+
+#[test]
+fn unreleased_section_is_replaced_then_released() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("CHANGELOG.md"), "# Changelog\n\n## v0.0.1\n\n- First release\n").unwrap();
+    git(&fx.work, &["add", "CHANGELOG.md"]);
+    git(&fx.work, &["commit", "-q", "-m", "Add changelog"]);
+    git(&fx.work, &["tag", "-a", "v0.0.1", "-m", "v0.0.1"]);
+    commit_message(&fx, "a.txt", "feat: add export");
+    fx.odin(&["changelog", "--write"]);
+    commit_message(&fx, "b.txt", "fix: handle empty input");
+    fx.odin(&["changelog", "--write"]);
+
+    let changelog = fs::read_to_string(fx.work.join("CHANGELOG.md")).unwrap();
+    assert_eq!(changelog.matches("## Unreleased").count(), 1, "{}", changelog);
+    assert_eq!(changelog.matches("add export").count(), 1, "{}", changelog);
+    assert!(changelog.contains("- handle empty input ("), "{}", changelog);
+    assert!(changelog.ends_with(")\n\n## v0.0.1\n\n- First release\n"), "{}", changelog);
+
+    git(&fx.work, &["commit", "-q", "-am", "Update changelog"]);
+    commit_message(&fx, "c.txt", "feat: add import");
+    let out = fx.odin_with_env(&["release", "minor", "--yes"], &[("PATH", &path)], "");
+    assert!(out.contains("Released v0.1.0."), "{}", out);
+    let released = fs::read_to_string(fx.work.join("CHANGELOG.md")).unwrap();
+    let notes = &fx.gh_calls()[0][6];
+    assert!(notes.contains("- add import ("), "{}", notes);
+    assert_eq!(released, format!("# Changelog\n\n## v0.1.0\n\n{}\n## v0.0.1\n\n- First release\n", notes));
+}
+
+#[test]
+fn release_uses_the_changelog() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("CHANGELOG.md"), "# Changelog\n").unwrap();
+    commit_message(&fx, "a.txt", "fix: handle empty input");
+
+    let out = fx.odin_with_env(&["release", "--yes"], &[("PATH", &path)], "");
+    assert!(out.contains("Released v0.0.1."), "{}", out);
+    let changelog = fs::read_to_string(fx.work.join("CHANGELOG.md")).unwrap();
+    assert!(changelog.starts_with("# Changelog\n\n## v0.0.1\n\n### Bug Fixes\n\n- handle empty input ("), "{}", changelog);
+    assert_eq!(git(&fx.work, &["show", "--name-only", "--format=%s", "v0.0.1"]).lines().last(), Some("CHANGELOG.md"));
+    assert!(fx.gh_calls()[0][6].starts_with("### Bug Fixes\n\n- handle empty input ("));
+}
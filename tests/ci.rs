@@ -0,0 +1,65 @@
+//! Runs `odin ci init` and `odin ci status` in a cloned project.
+
+mod common;
+
+use std::fs;
+use common::Fixture;
+
+#[test]
+fn init_detects_rust_and_writes_the_release_workflow() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n").unwrap();
+
+    let out = fx.odin(&["ci", "init", "--release"]);
+    assert!(out.contains("Wrote .github/workflows/ci.yml\nWrote .github/workflows/release.yml"), "{}", out);
+    let ci = fs::read_to_string(fx.work.join(".github/workflows/ci.yml")).unwrap();
+    assert!(ci.contains("    branches: [main]\n"), "{}", ci);
+    assert!(ci.contains("cargo clippy --workspace --all-targets -- -D warnings"), "{}", ci);
+    let release = fs::read_to_string(fx.work.join(".github/workflows/release.yml")).unwrap();
+    assert!(release.contains("asset: demo-linux-x86_64\n"), "{}", release);
+    assert!(release.contains("asset: demo-macos-aarch64\n"), "{}", release);
+    assert!(release.contains("cp target/release/demo ${{ matrix.asset }}"), "{}", release);
+
+    let out = fx.odin(&["ci", "init"]);
+    assert!(out.contains(".github/workflows/ci.yml already exists. Pass --force to overwrite it."), "{}", out);
+}
+
+#[test]
+fn init_for_node_and_python() {
+    let fx = Fixture::new();
+    let out = fx.odin(&["ci", "init"]);
+    assert!(out.contains("Could not detect the project's language. Pass --lang rust, python or node."), "{}", out);
+
+    fs::write(fx.work.join("package.json"), "{}").unwrap();
+    fx.odin(&["ci", "init"]);
+    let ci = fs::read_to_string(fx.work.join(".github/workflows/ci.yml")).unwrap();
+    assert!(ci.contains("run: npm install\n") && !ci.contains("cache: npm"), "{}", ci);
+
+    let out = fx.odin(&["ci", "init", "--lang", "python", "--release"]);
+    assert!(out.contains("isn't available for Python or Node projects"), "{}", out);
+    fs::write(fx.work.join("requirements.txt"), "requests\n").unwrap();
+    fx.odin(&["ci", "init", "--lang", "python", "--force"]);
+    let ci = fs::read_to_string(fx.work.join(".github/workflows/ci.yml")).unwrap();
+    assert!(ci.contains("run: pip install -r requirements.txt pytest\n"), "{}", ci);
+}
+
+#[test]
+fn status_shows_the_latest_run_of_each_workflow() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fx.gh_reply(
+        "run list",
+        r#"[
+            {"workflowName":"CI","status":"in_progress","conclusion":"","headBranch":"feature/x","displayTitle":"Add x","createdAt":"2026-10-19T12:30:00Z"},
+            {"workflowName":"CI","status":"completed","conclusion":"failure","headBranch":"main","displayTitle":"Old","createdAt":"2026-10-18T09:00:00Z"},
+            {"workflowName":"Release","status":"completed","conclusion":"success","headBranch":"v1.0.0","displayTitle":"Release v1.0.0","createdAt":"2026-10-17T08:15:00Z"}
+        ]"#,
+    );
+
+    let out = fx.odin_with_env(&["ci", "status", "--branch", "main"], &[("PATH", &path)], "");
+    assert_eq!(
+        out.trim_end(),
+        "CI       in progress  feature/x  2026-10-19 12:30  Add x\nRelease  success      v1.0.0     2026-10-17 08:15  Release v1.0.0"
+    );
+    assert_eq!(fx.gh_calls()[0][6..], ["--branch", "main"]);
+}
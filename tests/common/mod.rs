@@ -0,0 +1,183 @@
+//! Shared fixture for the integration tests: throwaway repositories with a
+//! local bare remote, and a helper to run the `odin` binary against them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+pub struct Fixture {
+    pub tmp: TempDir,
+    pub remote: PathBuf,
+    pub work: PathBuf,
+}
+
+impl Fixture {
+    /// A bare remote on `main` holding an Odin project (CONFIG.toml), and a clone of it.
+    pub fn new() -> Self {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("home")).unwrap();
+        let remote = tmp.path().join("remote.git");
+        let work = tmp.path().join("work");
+        seed(tmp.path(), &remote, &work, "work");
+        Fixture { tmp, remote, work }
+    }
+
+    /// Another project like `work`, at `<tmp>/<name>` with its remote at `<tmp>/<name>.git`.
+    pub fn project(&self, name: &str) -> PathBuf {
+        let dir = self.tmp.path().join(name);
+        seed(self.tmp.path(), &self.tmp.path().join(format!("{}.git", name)), &dir, name);
+        dir
+    }
+
+    /// Writes the registry (`~/.odin/projects.json`) with the given names, paths and tags.
+    pub fn register(&self, projects: &[(&str, &Path, &[&str])]) {
+        let entries: Vec<String> = projects
+            .iter()
+            .map(|(name, path, tags)| {
+                let tags: Vec<String> = tags.iter().map(|t| format!("{:?}", t)).collect();
+                format!("{{\"name\": {:?}, \"path\": {:?}, \"tags\": [{}]}}", name, path.display().to_string(), tags.join(", "))
+            })
+            .collect();
+        let dir = self.tmp.path().join("home/.odin");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("projects.json"), format!("[{}]", entries.join(", "))).unwrap();
+    }
+
+    /// A second clone, used to push commits the working copy does not have.
+    pub fn other_clone(&self) -> PathBuf {
+        let other = self.tmp.path().join("other");
+        if !other.exists() {
+            git(self.tmp.path(), &["clone", "-q", self.remote.to_str().unwrap(), other.to_str().unwrap()]);
+        }
+        other
+    }
+
+    pub fn update(&self, input: &str) -> String {
+        self.update_with(&[], input)
+    }
+
+    pub fn update_with(&self, args: &[&str], input: &str) -> String {
+        self.update_with_env(args, &[], input)
+    }
+
+    pub fn update_with_env(&self, args: &[&str], env: &[(&str, &str)], input: &str) -> String {
+        let args: Vec<&str> = std::iter::once("update").chain(args.iter().copied()).collect();
+        self.odin_with_env(&args, env, input)
+    }
+
+    /// Runs `odin <args>` in the working copy and returns stdout followed by stderr.
+    pub fn odin(&self, args: &[&str]) -> String {
+        self.odin_with_env(args, &[], "")
+    }
+
+    pub fn odin_with_env(&self, args: &[&str], env: &[(&str, &str)], input: &str) -> String {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_odin"))
+            .args(args)
+            .current_dir(&self.work)
+            .envs(git_env(self.tmp.path()))
+            .envs(env.iter().copied())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))
+    }
+
+    /// Puts a stand-in `gh` on `PATH` that records its arguments and fails
+    /// `gh pr view` (no existing pull request). Returns the `PATH` to pass.
+    pub fn fake_gh(&self) -> String {
+        let bin = self.tmp.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let log = self.tmp.path().join("gh.log");
+        let script = format!(
+            "#!/bin/sh\nfor arg in \"$@\"; do printf '%s\\0' \"$arg\" >> '{}'; done\nprintf '\\036' >> '{}'\n[ \"$1 $2\" = \"api -X\" ] && {{ cat; printf '\\036'; }} >> '{}.input'\nreply='{}'/\"$(printf '%s-%s' \"$1\" \"$2\" | tr / _)\"\n[ -f \"$reply\" ] && exec cat \"$reply\"\n[ -f \"$reply.fail\" ] && {{ cat \"$reply.fail\" >&2; exit 1; }}\n[ \"$1 $2\" = \"pr view\" ] && exit 1\nexit 0\n",
+            log.display(),
+            log.display(),
+            log.display(),
+            self.tmp.path().join("gh-replies").display()
+        );
+        let gh = bin.join("gh");
+        fs::write(&gh, script).unwrap();
+        Command::new("chmod").arg("+x").arg(&gh).status().unwrap();
+        format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default())
+    }
+
+    /// Makes `fake_gh` print `output` for `command`, e.g. `"repo view"` or `"api repos/{owner}/{repo}/labels"`.
+    pub fn gh_reply(&self, command: &str, output: &str) {
+        let dir = self.tmp.path().join("gh-replies");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(command.replacen(' ', "-", 1).replace('/', "_")), output).unwrap();
+    }
+
+    /// Makes `fake_gh` print `stderr` and fail for `command`.
+    pub fn gh_fail(&self, command: &str, stderr: &str) {
+        let dir = self.tmp.path().join("gh-replies");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}.fail", command.replacen(' ', "-", 1).replace('/', "_"))), stderr).unwrap();
+    }
+
+    /// The request bodies sent to `fake_gh` by `gh api -X <method> ... --input -`, in order.
+    pub fn gh_inputs(&self) -> Vec<String> {
+        let inputs = fs::read_to_string(self.tmp.path().join("gh.log.input")).unwrap_or_default();
+        inputs.split('\x1e').filter(|i| !i.is_empty()).map(str::to_string).collect()
+    }
+
+    /// The argument lists `fake_gh` was called with, in order.
+    pub fn gh_calls(&self) -> Vec<Vec<String>> {
+        let log = fs::read_to_string(self.tmp.path().join("gh.log")).unwrap_or_default();
+        log.split('\x1e')
+            .filter(|call| !call.is_empty())
+            .map(|call| call.split('\0').filter(|a| !a.is_empty()).map(str::to_string).collect())
+            .collect()
+    }
+
+    pub fn remote_head(&self, branch: &str) -> String {
+        git(&self.remote, &["rev-parse", branch])
+    }
+}
+
+/// Creates a bare remote seeded with an Odin project called `name`, and clones it to `work`.
+fn seed(root: &Path, remote: &Path, work: &Path, name: &str) {
+    git(root, &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+    let seed = root.join(format!("{}-seed", name));
+    git(root, &["init", "-q", "-b", "main", seed.to_str().unwrap()]);
+    fs::write(seed.join("CONFIG.toml"), format!("[app]\nname = \"{}\"\n", name)).unwrap();
+    git(&seed, &["add", "."]);
+    git(&seed, &["commit", "-q", "-m", "Initial commit"]);
+    git(&seed, &["push", "-q", remote.to_str().unwrap(), "main"]);
+    git(root, &["clone", "-q", remote.to_str().unwrap(), work.to_str().unwrap()]);
+}
+
+fn git_env(root: &Path) -> Vec<(&'static str, String)> {
+    vec![
+        ("HOME", root.join("home").display().to_string()),
+        ("GIT_CONFIG_NOSYSTEM", "1".to_string()),
+        ("GIT_AUTHOR_NAME", "Odin Test".to_string()),
+        ("GIT_AUTHOR_EMAIL", "odin@example.com".to_string()),
+        ("GIT_COMMITTER_NAME", "Odin Test".to_string()),
+        ("GIT_COMMITTER_EMAIL", "odin@example.com".to_string()),
+    ]
+}
+
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let root = dir.ancestors().find(|d| d.join("home").exists()).unwrap_or(dir);
+    let output = Command::new("git").args(args).current_dir(dir).envs(git_env(root)).output().unwrap();
+    assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+pub fn commit(dir: &Path, file: &str) {
+    fs::write(dir.join(file), file).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", file]);
+}
+
+pub fn committed_files(dir: &Path) -> String {
+    git(dir, &["show", "--name-only", "--format=", "HEAD"])
+}
@@ -0,0 +1,18 @@
+//! Runs `odin foreach` over registered projects.
+
+mod common;
+
+use common::Fixture;
+
+#[test]
+fn output_that_is_not_utf8_is_streamed_to_the_end() {
+    let fx = Fixture::new();
+    fx.register(&[("work", &fx.work, &[])]);
+
+    // More output than a pipe holds follows the invalid byte, so an undrained pipe would hang
+    let script = "printf 'bad \\377 byte\\n'; yes line | head -n 20000; echo done";
+    let out = fx.odin(&["foreach", "--", "sh", "-c", script]);
+    assert!(out.contains("bad \u{fffd} byte"), "{}", &out[..200.min(out.len())]);
+    assert_eq!(out.matches(" line\n").count(), 20000);
+    assert!(out.contains(" done\n"), "{}", &out[out.len().saturating_sub(200)..]);
+}
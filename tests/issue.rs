@@ -0,0 +1,62 @@
+//! Checks what `odin issue` asks `gh` to do, using a stand-in `gh` on `PATH`.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use common::{git, Fixture};
+
+#[test]
+fn new_passes_title_and_body() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+
+    fx.odin_with_env(&["issue", "new", "-t", "Crash on start", "-b", "Steps:\n1. run it"], &[("PATH", &path)], "");
+    assert_eq!(fx.gh_calls()[0], ["issue", "create", "--title", "Crash on start", "--body", "Steps:\n1. run it"]);
+}
+
+#[test]
+fn new_without_a_title_needs_a_terminal() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+
+    let out = fx.odin_with_env(&["issue", "new"], &[("PATH", &path)], "Crash\n");
+    assert!(out.contains("stdin is not a terminal. Pass --title <title> or --edit."), "{}", out);
+    assert!(fx.gh_calls().is_empty());
+}
+
+#[test]
+fn edit_takes_the_title_from_the_first_line() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    // The "editor" writes a title and a Markdown body above the ignored block
+    let editor = fx.tmp.path().join("editor.sh");
+    fs::write(&editor, "#!/bin/sh\n{ printf 'Crash on start\\n\\n## Steps\\nrun it\\n'; cat \"$1\"; } > \"$1.new\" && mv \"$1.new\" \"$1\"\n").unwrap();
+    Command::new("chmod").arg("+x").arg(&editor).status().unwrap();
+    let editor = editor.display().to_string();
+
+    fx.odin_with_env(&["issue", "new", "--edit"], &[("PATH", &path), ("EDITOR", &editor), ("VISUAL", "")], "");
+    assert_eq!(fx.gh_calls()[0], ["issue", "create", "--title", "Crash on start", "--body", "## Steps\nrun it"]);
+}
+
+#[test]
+fn close_with_a_comment() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+
+    fx.odin_with_env(&["issue", "close", "12", "-c", "Fixed in 1.2"], &[("PATH", &path)], "");
+    assert_eq!(fx.gh_calls()[0], ["issue", "close", "12", "--comment", "Fixed in 1.2"]);
+}
+
+#[test]
+fn list_across_projects_with_a_github_remote() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    let local = fx.project("local");
+    git(&fx.work, &["remote", "set-url", "origin", "https://github.com/me/work.git"]);
+    fx.register(&[("work", &fx.work, &[]), ("local", &local, &[])]);
+    fx.gh_reply("issue list", r#"[{"number": 3, "title": "Crash", "labels": [{"name": "bug"}], "author": {"login": "ann"}}]"#);
+
+    let out = fx.odin_with_env(&["issue", "list", "--all-projects"], &[("PATH", &path)], "");
+    assert_eq!(out, "work (1 open)\n  #3     Crash [bug] (@ann)\n");
+}
@@ -0,0 +1,21 @@
+//! Runs `odin open --web` with a stand-in `gh`.
+
+mod common;
+
+use common::Fixture;
+
+#[test]
+fn web_reports_a_failing_gh() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fx.register(&[("work", &fx.work, &[])]);
+
+    let out = fx.odin_with_env(&["open", "work", "--web"], &[("PATH", &path)], "");
+    assert_eq!(out, "");
+    assert_eq!(fx.gh_calls(), [["browse"]]);
+
+    fx.gh_fail("browse ", "none of the git remotes point to a known GitHub host\n");
+    let out = fx.odin_with_env(&["open", "work", "--web"], &[("PATH", &path)], "");
+    assert!(out.contains("none of the git remotes point to a known GitHub host"), "{}", out);
+    assert!(out.contains(&format!("Failed to open {}: gh browse failed", fx.work.display())), "{}", out);
+}
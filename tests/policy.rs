@@ -0,0 +1,146 @@
+//! Runs `odin policy apply` with a stand-in `gh` that reports labels and branch protection.
+
+mod common;
+
+use std::fs;
+use common::Fixture;
+
+const POLICY: &str = "[[labels]]\nname = \"bug\"\ncolor = \"#D73A4A\"\ndescription = \"Something isn't working\"\n\n[[labels]]\nname = \"docs\"\ncolor = \"0075ca\"\n\n[protection]\nrequired_reviews = 1\nallow_force_pushes = false\n";
+const PROTECTION: &str = "api repos/{owner}/{repo}/branches/main/protection";
+const LABELS: &str = "{\"name\":\"Bug\",\"color\":\"d73a4a\",\"description\":\"Something isn't working\"}\n{\"name\":\"wontfix\",\"color\":\"ffffff\",\"description\":\"\"}\n";
+
+#[test]
+fn check_reports_drift_without_changing_anything() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("POLICY.toml"), POLICY).unwrap();
+    fx.gh_reply("api repos/{owner}/{repo}/labels", LABELS);
+    fx.gh_fail(PROTECTION, "gh: Branch not protected (HTTP 404)\n");
+
+    let out = fx.odin_with_env(&["policy", "apply", "--check"], &[("PATH", &path)], "");
+    assert!(out.contains("work: 4 differences from the policy"), "{}", out);
+    assert!(out.contains("  label 'bug': name 'Bug' -> 'bug'\n"), "{}", out);
+    assert!(out.contains("  label 'docs' is missing\n"), "{}", out);
+    assert!(out.contains("  branch 'main' is not protected\n  main: required reviews 0 -> 1\n"), "{}", out);
+    assert!(fx.gh_inputs().is_empty());
+}
+
+#[test]
+fn apply_creates_labels_and_protects_the_default_branch() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("POLICY.toml"), POLICY).unwrap();
+    fx.gh_reply("api repos/{owner}/{repo}/labels", LABELS);
+    fx.gh_fail(PROTECTION, "gh: Branch not protected (HTTP 404)\n");
+
+    let out = fx.odin_with_env(&["policy", "apply"], &[("PATH", &path)], "");
+    assert!(out.contains("Applied the policy to 1 project."), "{}", out);
+    let writes: Vec<Vec<String>> = fx.gh_calls().into_iter().filter(|call| call[1] == "-X").collect();
+    assert_eq!(writes[0][2..4], ["PATCH", "repos/{owner}/{repo}/labels/Bug"]);
+    assert_eq!(writes[1][2..4], ["POST", "repos/{owner}/{repo}/labels"]);
+    assert_eq!(writes[2][2..4], ["PUT", "repos/{owner}/{repo}/branches/main/protection"]);
+
+    let bodies: Vec<serde_json::Value> = fx.gh_inputs().iter().map(|b| serde_json::from_str(b).unwrap()).collect();
+    assert_eq!(bodies[0]["new_name"], "bug");
+    assert_eq!(bodies[1], serde_json::json!({ "name": "docs", "color": "0075ca", "description": "" }));
+    assert_eq!(bodies[2]["required_pull_request_reviews"]["required_approving_review_count"], 1);
+    assert_eq!(bodies[2]["required_status_checks"], serde_json::Value::Null);
+    assert_eq!(bodies[2]["allow_force_pushes"], false);
+}
+
+#[test]
+fn protection_keeps_settings_the_policy_leaves_out() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("POLICY.toml"), "[protection]\nrequired_reviews = 2\n").unwrap();
+    fx.gh_reply(
+        PROTECTION,
+        r#"{
+            "required_status_checks": {"strict": true, "contexts": ["ci"], "checks": [{"context": "ci", "app_id": 15368}]},
+            "enforce_admins": {"enabled": true},
+            "required_pull_request_reviews": {
+                "required_approving_review_count": 1,
+                "dismiss_stale_reviews": true,
+                "require_last_push_approval": true,
+                "bypass_pull_request_allowances": {"users": [{"login": "release-bot"}], "teams": [], "apps": []}
+            },
+            "restrictions": null,
+            "required_linear_history": {"enabled": true},
+            "required_conversation_resolution": {"enabled": true},
+            "allow_force_pushes": {"enabled": false},
+            "allow_deletions": {"enabled": false},
+            "block_creations": {"enabled": false},
+            "lock_branch": {"enabled": false},
+            "allow_fork_syncing": {"enabled": true}
+        }"#,
+    );
+
+    let out = fx.odin_with_env(&["policy", "apply"], &[("PATH", &path)], "");
+    assert!(out.contains("work: 1 difference from the policy\n  main: required reviews 1 -> 2\n"), "{}", out);
+    let body: serde_json::Value = serde_json::from_str(&fx.gh_inputs()[0]).unwrap();
+    assert_eq!(body["required_status_checks"], serde_json::json!({ "strict": true, "checks": [{ "context": "ci", "app_id": 15368 }] }));
+    assert_eq!(body["enforce_admins"], true);
+    assert_eq!(body["required_linear_history"], true);
+    assert_eq!(body["required_conversation_resolution"], true);
+    assert_eq!(body["allow_fork_syncing"], true);
+    assert_eq!(body["lock_branch"], false);
+    let reviews = &body["required_pull_request_reviews"];
+    assert_eq!(reviews["required_approving_review_count"], 2);
+    assert_eq!(reviews["dismiss_stale_reviews"], true);
+    assert_eq!(reviews["require_last_push_approval"], true);
+    assert_eq!(reviews["bypass_pull_request_allowances"], serde_json::json!({ "users": ["release-bot"], "teams": [], "apps": [] }));
+    assert!(reviews.get("dismissal_restrictions").is_none());
+}
+
+#[test]
+fn protection_keeps_a_pull_request_rule_without_approvals() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("POLICY.toml"), "[protection]\nrequired_checks = [\"ci\"]\n").unwrap();
+    fx.gh_reply(
+        PROTECTION,
+        r#"{
+            "required_pull_request_reviews": {"required_approving_review_count": 0, "dismiss_stale_reviews": true},
+            "enforce_admins": {"enabled": false}
+        }"#,
+    );
+
+    let out = fx.odin_with_env(&["policy", "apply"], &[("PATH", &path)], "");
+    assert!(out.contains("  main: required checks (none) -> ci\n"), "{}", out);
+    let body: serde_json::Value = serde_json::from_str(&fx.gh_inputs()[0]).unwrap();
+    let reviews = &body["required_pull_request_reviews"];
+    assert_eq!(reviews["required_approving_review_count"], 0);
+    assert_eq!(reviews["dismiss_stale_reviews"], true);
+}
+
+#[test]
+fn protection_that_cannot_be_read_is_left_alone() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fs::write(fx.work.join("POLICY.toml"), "[protection]\nrequired_reviews = 1\n").unwrap();
+    fx.gh_fail(PROTECTION, "gh: Resource not accessible by integration (HTTP 403)\n");
+
+    let out = fx.odin_with_env(&["policy", "apply"], &[("PATH", &path)], "");
+    assert!(out.contains("work: could not read the protection of 'main': gh: Resource not accessible by integration (HTTP 403)"), "{}", out);
+    assert!(!out.contains("not protected"), "{}", out);
+    assert!(fx.gh_inputs().is_empty());
+}
+
+#[test]
+fn global_policy_applies_across_the_registry() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    let other = fx.project("other");
+    for dir in [&fx.work, &other] {
+        common::git(dir, &["remote", "set-url", "origin", "https://github.com/me/x.git"]);
+    }
+    fx.register(&[("demo", &fx.work, &["work"]), ("other", &other, &["work"])]);
+    fs::write(fx.tmp.path().join("home/.odin/policy.toml"), "[[labels]]\nname = \"bug\"\ncolor = \"d73a4a\"\n").unwrap();
+    fs::write(fx.work.join("POLICY.toml"), "labels = []\n").unwrap();
+    fx.gh_reply("api repos/{owner}/{repo}/labels", "{\"name\":\"bug\",\"color\":\"000000\",\"description\":\"\"}\n");
+
+    let out = fx.odin_with_env(&["policy", "apply", "--tag", "work", "--check"], &[("PATH", &path)], "");
+    assert!(out.contains("demo: matches the policy"), "{}", out);
+    assert!(out.contains("other: 1 difference from the policy\n  label 'bug': color 000000 -> d73a4a\n"), "{}", out);
+    assert!(out.contains("1 of 2 projects differ from the policy."), "{}", out);
+}
@@ -0,0 +1,85 @@
+//! Checks what `odin pr` asks `gh` to do, using a stand-in `gh` on `PATH`.
+
+mod common;
+
+use std::fs;
+use common::{commit, git, Fixture};
+
+#[test]
+fn create_pushes_and_prefills_from_the_commits() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    git(&fx.work, &["checkout", "-q", "-b", "feature/user-login"]);
+    commit(&fx.work, "form.txt");
+    commit(&fx.work, "session.txt");
+
+    let out = fx.odin_with_env(&["pr", "create"], &[("PATH", &path)], "");
+    assert!(out.contains("Opening a pull request into 'main': User login"), "{}", out);
+    assert_eq!(fx.remote_head("feature/user-login"), git(&fx.work, &["rev-parse", "HEAD"]));
+    let create = fx.gh_calls().into_iter().find(|c| c[..2] == ["pr", "create"]).unwrap();
+    assert_eq!(
+        create,
+        ["pr", "create", "--base", "main", "--head", "feature/user-login", "--title", "User login", "--body", "- form.txt\n- session.txt"]
+    );
+}
+
+#[test]
+fn a_single_commit_becomes_the_title_and_body() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    git(&fx.work, &["checkout", "-q", "-b", "fix/crash"]);
+    fs::write(fx.work.join("a.txt"), "a").unwrap();
+    git(&fx.work, &["add", "a.txt"]);
+    git(&fx.work, &["commit", "-q", "-m", "Fix crash on start", "-m", "The config was read too early."]);
+
+    fx.odin_with_env(&["pr", "create", "--draft"], &[("PATH", &path)], "");
+    let create = fx.gh_calls().into_iter().find(|c| c[..2] == ["pr", "create"]).unwrap();
+    assert_eq!(&create[6..], ["--title", "Fix crash on start", "--body", "The config was read too early.", "--draft"]);
+}
+
+#[test]
+fn create_refuses_on_the_default_branch() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    let out = fx.odin_with_env(&["pr", "create"], &[("PATH", &path)], "");
+    assert!(out.contains("'main' is the base branch"), "{}", out);
+}
+
+#[test]
+fn create_does_not_push_a_branch_without_new_commits() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    git(&fx.work, &["checkout", "-q", "-b", "feature/empty"]);
+
+    let out = fx.odin_with_env(&["pr", "create"], &[("PATH", &path)], "");
+    assert!(out.contains("'feature/empty' has no commits that aren't already on 'main'."), "{}", out);
+    assert_eq!(git(&fx.remote, &["branch", "--list", "feature/empty"]), "");
+}
+
+#[test]
+fn list_shows_open_pull_requests() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    fx.gh_reply("pr list", "[]");
+    let out = fx.odin_with_env(&["pr", "list"], &[("PATH", &path)], "");
+    assert_eq!(out, "No open pull requests.\n");
+
+    fx.gh_reply("pr list", r#"[{"number": 7, "title": "Login", "headRefName": "feature/login", "author": {"login": "ann"}, "isDraft": true}]"#);
+    let out = fx.odin_with_env(&["pr", "list"], &[("PATH", &path)], "");
+    assert_eq!(out, "#7     Login [draft] (feature/login, @ann)\n");
+    assert_eq!(fx.gh_calls()[0], ["pr", "list", "--state", "open", "--json", "number,title,headRefName,author,isDraft"]);
+}
+
+#[test]
+fn merge_uses_the_configured_strategy() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n[pr]\nmerge = \"squash\"\n", config)).unwrap();
+
+    fx.odin_with_env(&["pr", "merge", "7"], &[("PATH", &path)], "");
+    fx.odin_with_env(&["pr", "merge", "8", "--rebase", "--keep-branch"], &[("PATH", &path)], "");
+    let calls = fx.gh_calls();
+    assert_eq!(calls[0], ["pr", "merge", "7", "--squash", "--delete-branch"]);
+    assert_eq!(calls[1], ["pr", "merge", "8", "--rebase"]);
+}
@@ -0,0 +1,82 @@
+//! Runs `odin release` against a local bare remote with a stand-in `gh`.
+
+mod common;
+
+use std::fs;
+use common::{commit, git, Fixture};
+
+fn add_file(fx: &Fixture, name: &str, content: &str) {
+    fs::write(fx.work.join(name), content).unwrap();
+    git(&fx.work, &["add", name]);
+    git(&fx.work, &["commit", "-q", "-m", &format!("Add {}", name)]);
+}
+
+#[test]
+fn bumps_cargo_toml_tags_and_publishes() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    add_file(&fx, "Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.4\"\n\n[dependencies]\nserde = { version = \"1.0\" }\n");
+    git(&fx.work, &["tag", "-a", "v0.1.4", "-m", "v0.1.4"]);
+    commit(&fx.work, "feature.txt");
+
+    let out = fx.odin_with_env(&["release", "minor", "--yes", "--draft"], &[("PATH", &path)], "");
+    assert!(out.contains("Released v0.2.0 as a draft."), "{}", out);
+    let manifest = fs::read_to_string(fx.work.join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("version = \"0.2.0\"\n\n[dependencies]\nserde = { version = \"1.0\" }"), "{}", manifest);
+    assert_eq!(git(&fx.work, &["log", "-1", "--format=%s"]), "Release v0.2.0");
+    assert_eq!(git(&fx.remote, &["rev-parse", "v0.2.0^{commit}"]), fx.remote_head("main"));
+
+    let release = &fx.gh_calls()[0];
+    assert_eq!(release[..5], ["release", "create", "v0.2.0", "--title", "v0.2.0"]);
+    assert!(release[6].contains("- feature.txt ("), "{:?}", release);
+    assert!(!release[6].contains("Add Cargo.toml"), "{:?}", release);
+    assert_eq!(release[7], "--draft");
+}
+
+#[test]
+fn explicit_version_in_package_json() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    add_file(&fx, "package.json", "{\n  \"name\": \"demo\",\n  \"version\": \"1.4.2\"\n}\n");
+
+    let out = fx.odin_with_env(&["release", "2.0.0", "--yes"], &[("PATH", &path)], "");
+    assert!(out.contains("Released v2.0.0."), "{}", out);
+    assert_eq!(fs::read_to_string(fx.work.join("package.json")).unwrap(), "{\n  \"name\": \"demo\",\n  \"version\": \"2.0.0\"\n}\n");
+}
+
+#[test]
+fn refuses_older_versions_and_dirty_trees() {
+    let fx = Fixture::new();
+    add_file(&fx, "pyproject.toml", "[project]\nname = \"demo\"\nversion = \"1.2.0\"\n");
+
+    let out = fx.odin(&["release", "1.1.0", "--yes"]);
+    assert!(out.contains("1.1.0 is not newer than the current version 1.2.0."), "{}", out);
+
+    fs::write(fx.work.join("pyproject.toml"), "[project]\nversion = \"9.9.9\"\n").unwrap();
+    let out = fx.odin(&["release", "--yes"]);
+    assert!(out.contains("You have uncommitted changes."), "{}", out);
+    assert_eq!(git(&fx.work, &["tag"]), "");
+}
+
+#[test]
+fn orders_pre_releases_by_identifier() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    add_file(&fx, "package.json", "{\n  \"name\": \"demo\",\n  \"version\": \"1.0.0-rc.9\"\n}\n");
+
+    let out = fx.odin(&["release", "1.0.0-rc.8", "--yes"]);
+    assert!(out.contains("1.0.0-rc.8 is not newer than the current version 1.0.0-rc.9."), "{}", out);
+    let out = fx.odin(&["release", "1.0.0-beta.10", "--yes"]);
+    assert!(out.contains("1.0.0-beta.10 is not newer than the current version 1.0.0-rc.9."), "{}", out);
+
+    let out = fx.odin_with_env(&["release", "1.0.0-rc.10", "--yes"], &[("PATH", &path)], "");
+    assert!(out.contains("Released v1.0.0-rc.10."), "{}", out);
+}
+
+#[test]
+fn asks_before_releasing() {
+    let fx = Fixture::new();
+    let out = fx.odin(&["release"]);
+    assert!(out.contains("New version:     0.0.1"), "{}", out);
+    assert!(out.contains("Cannot ask \"Release v0.0.1\": stdin is not a terminal. Pass --yes."), "{}", out);
+}
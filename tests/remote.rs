@@ -0,0 +1,73 @@
+//! Runs `odin remote sync` with a stand-in `gh` that reports the current settings.
+
+mod common;
+
+use std::fs;
+use common::Fixture;
+
+const CURRENT: &str = r#"{"nameWithOwner":"me/demo","description":"Old text","homepageUrl":"","repositoryTopics":[{"name":"cli"},{"name":"legacy"}],"visibility":"PRIVATE","hasIssuesEnabled":true,"hasWikiEnabled":true}"#;
+
+fn configure(fx: &Fixture, github: &str) {
+    fs::write(fx.work.join("CONFIG.toml"), format!("[app]\nname = \"demo\"\n\n[github]\n{}", github)).unwrap();
+    fx.gh_reply("repo view", CURRENT);
+}
+
+#[test]
+fn dry_run_shows_the_diff_only() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    configure(&fx, "description = \"A demo\"\nhomepage = \"https://example.com\"\ntopics = [\"CLI\", \"rust\"]\nwiki = false\nissues = true\n");
+
+    let out = fx.odin_with_env(&["remote", "sync", "--dry-run"], &[("PATH", &path)], "");
+    assert!(out.contains("Changes to me/demo:"), "{}", out);
+    assert!(out.contains("  description: \"Old text\" -> \"A demo\"\n"), "{}", out);
+    assert!(out.contains("  homepage: (none) -> \"https://example.com\"\n"), "{}", out);
+    assert!(out.contains("  topics: +rust -legacy\n"), "{}", out);
+    assert!(out.contains("  wiki: enabled -> disabled\n"), "{}", out);
+    assert!(!out.contains("issues:"), "{}", out);
+    assert!(out.contains("Dry run: nothing was changed."), "{}", out);
+    assert!(fx.gh_calls().iter().all(|call| call[..2] != ["repo", "edit"]));
+}
+
+#[test]
+fn applies_changes_with_one_edit() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    configure(&fx, "description = \"A demo\"\ntopics = [\"cli\", \"rust\"]\nwiki = false\n");
+
+    let out = fx.odin_with_env(&["remote", "sync"], &[("PATH", &path)], "");
+    assert!(out.contains("Updated 3 settings."), "{}", out);
+    let edit = fx.gh_calls().into_iter().find(|call| call[..2] == ["repo", "edit"]).unwrap();
+    assert_eq!(
+        edit,
+        ["repo", "edit", "--description", "A demo", "--add-topic", "rust", "--remove-topic", "legacy", "--enable-wiki=false"]
+    );
+}
+
+#[test]
+fn visibility_changes_need_confirmation() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    configure(&fx, "visibility = \"public\"\n");
+
+    let out = fx.odin_with_env(&["remote", "sync"], &[("PATH", &path)], "");
+    assert!(out.contains("  visibility: private -> public\n"), "{}", out);
+    assert!(out.contains("Cannot ask \"Make me/demo public\": stdin is not a terminal. Pass --yes."), "{}", out);
+
+    let out = fx.odin_with_env(&["remote", "sync", "--yes"], &[("PATH", &path)], "");
+    assert!(out.contains("Updated 1 setting."), "{}", out);
+    let edit = fx.gh_calls().into_iter().find(|call| call[..2] == ["repo", "edit"]).unwrap();
+    assert_eq!(edit[2..], ["--visibility", "public", "--accept-visibility-change-consequences"]);
+}
+
+#[test]
+fn matching_settings_and_missing_section() {
+    let fx = Fixture::new();
+    let path = fx.fake_gh();
+    let out = fx.odin_with_env(&["remote", "sync"], &[("PATH", &path)], "");
+    assert!(out.contains("CONFIG.toml has no [github] settings to apply."), "{}", out);
+
+    configure(&fx, "description = \"Old text\"\ntopics = [\"legacy\", \"cli\"]\nvisibility = \"private\"\n");
+    let out = fx.odin_with_env(&["remote", "sync"], &[("PATH", &path)], "");
+    assert!(out.contains("me/demo already matches CONFIG.toml."), "{}", out);
+}
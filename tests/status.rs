@@ -0,0 +1,56 @@
+//! Checks that `odin status` reports the project state and leaves it untouched.
+
+mod common;
+
+use std::fs;
+use common::{commit, git, Fixture};
+
+#[test]
+fn shows_the_current_project() {
+    let fx = Fixture::new();
+    fx.register(&[("work", &fx.work, &["client"])]);
+    commit(&fx.work, "a.txt");
+    fs::write(fx.work.join("b.txt"), "b").unwrap();
+    git(&fx.work, &["add", "b.txt"]);
+    fs::write(fx.work.join("c.txt"), "c").unwrap();
+    let before = git(&fx.work, &["status", "--porcelain"]);
+
+    let out = fx.odin(&["status"]);
+    assert!(out.contains("Project:     work\n"), "{}", out);
+    assert!(out.contains("Registry:    registered as 'work', tags client"), "{}", out);
+    assert!(out.contains("Branch:      main"), "{}", out);
+    assert!(out.contains("Upstream:    origin/main (1 ahead, 0 behind)"), "{}", out);
+    assert!(out.contains("Changes:     1 staged, 0 unstaged, 1 untracked"), "{}", out);
+    assert!(out.contains(" a.txt (Odin Test, "), "{}", out);
+    assert!(out.contains(&format!("Remote:      {}", fx.remote.display())), "{}", out);
+    assert_eq!(git(&fx.work, &["status", "--porcelain"]), before);
+}
+
+#[test]
+fn json_output() {
+    let fx = Fixture::new();
+    commit(&fx.work, "a.txt");
+
+    let out = fx.odin(&["status", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["name"], "work");
+    assert_eq!(json["registered"], false);
+    assert_eq!(json["branch"], "main");
+    assert_eq!(json["ahead"], 1);
+    assert_eq!(json["last_commit"]["subject"], "a.txt");
+    assert_eq!(json["remote"], fx.remote.display().to_string());
+}
+
+#[test]
+fn group_lists_every_matching_project() {
+    let fx = Fixture::new();
+    let api = fx.project("api");
+    let gone = fx.tmp.path().join("gone");
+    fx.register(&[("work", &fx.work, &["backend"]), ("api", &api, &["backend"]), ("gone", &gone, &["backend"])]);
+
+    let out = fx.odin(&["status", "--tag", "backend", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let names: Vec<&str> = json.as_array().unwrap().iter().map(|r| r["registry_name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["work", "api", "gone"]);
+    assert_eq!(json[2]["exists"], false);
+}
@@ -0,0 +1,93 @@
+//! Runs `odin sync` over a registry of local projects with bare remotes.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use common::{commit, git, Fixture};
+
+/// Pushes a new commit to the remote of the project at `<tmp>/<name>` from a second clone.
+fn push_from_elsewhere(fx: &Fixture, name: &str) {
+    let remote = fx.tmp.path().join(format!("{}.git", name));
+    let other = fx.tmp.path().join(format!("{}-other", name));
+    git(fx.tmp.path(), &["clone", "-q", remote.to_str().unwrap(), other.to_str().unwrap()]);
+    commit(&other, "theirs.txt");
+    git(&other, &["push", "-q"]);
+}
+
+fn head(dir: &Path) -> String {
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+fn line<'a>(out: &'a str, name: &str) -> &'a str {
+    out.lines().find(|l| l.split_whitespace().next() == Some(name)).unwrap_or("")
+}
+
+#[test]
+fn pulls_behind_pushes_ahead_and_reports_the_rest() {
+    let fx = Fixture::new();
+    let behind = fx.project("behind");
+    push_from_elsewhere(&fx, "behind");
+    let ahead = fx.project("ahead");
+    commit(&ahead, "mine.txt");
+    let diverged = fx.project("diverged");
+    push_from_elsewhere(&fx, "diverged");
+    commit(&diverged, "mine.txt");
+    let dirty = fx.project("dirty");
+    push_from_elsewhere(&fx, "dirty");
+    fs::write(dirty.join("CONFIG.toml"), "[app]\nname = \"edited\"\n").unwrap();
+    let branch = fx.project("branch");
+    git(&branch, &["checkout", "-q", "-b", "feature"]);
+    let gone = fx.tmp.path().join("gone");
+    fx.register(&[
+        ("behind", &behind, &[]),
+        ("ahead", &ahead, &[]),
+        ("diverged", &diverged, &[]),
+        ("dirty", &dirty, &[]),
+        ("branch", &branch, &[]),
+        ("gone", &gone, &[]),
+        ("work", &fx.work, &[]),
+    ]);
+
+    let out = fx.odin(&["sync"]);
+    assert_eq!(line(&out, "behind"), "behind    pulled 1 commit", "{}", out);
+    assert_eq!(line(&out, "ahead"), "ahead     pushed 1 commit", "{}", out);
+    assert!(line(&out, "diverged").contains("skipped: diverged (1 ahead, 1 behind)"), "{}", out);
+    assert!(line(&out, "dirty").contains("skipped: 1 commit behind but has uncommitted changes"), "{}", out);
+    assert!(line(&out, "branch").contains("skipped: 'feature' has no upstream"), "{}", out);
+    assert!(line(&out, "gone").contains("skipped: directory is missing"), "{}", out);
+    assert!(line(&out, "work").contains("up to date"), "{}", out);
+    assert!(out.contains("1 pulled, 1 pushed, 1 up to date, 4 skipped, 0 failed."), "{}", out);
+
+    assert_eq!(head(&behind), git(&fx.tmp.path().join("behind.git"), &["rev-parse", "main"]));
+    assert_eq!(head(&ahead), git(&fx.tmp.path().join("ahead.git"), &["rev-parse", "main"]));
+    assert_eq!(git(&diverged, &["rev-list", "--count", "HEAD"]), "2");
+}
+
+#[test]
+fn pull_only_leaves_ahead_projects_alone() {
+    let fx = Fixture::new();
+    let ahead = fx.project("ahead");
+    commit(&ahead, "mine.txt");
+    let before = git(&fx.tmp.path().join("ahead.git"), &["rev-parse", "main"]);
+    fx.register(&[("ahead", &ahead, &[])]);
+
+    let out = fx.odin(&["sync", "--pull"]);
+    assert!(line(&out, "ahead").contains("skipped: 1 commit ahead; push not requested"), "{}", out);
+    assert_eq!(git(&fx.tmp.path().join("ahead.git"), &["rev-parse", "main"]), before);
+}
+
+#[test]
+fn tag_limits_which_projects_are_synced() {
+    let fx = Fixture::new();
+    let tagged = fx.project("tagged");
+    commit(&tagged, "mine.txt");
+    let other = fx.project("other");
+    commit(&other, "mine.txt");
+    fx.register(&[("tagged", &tagged, &["work"]), ("other", &other, &[])]);
+
+    let out = fx.odin(&["sync", "--tag", "work", "--push"]);
+    assert!(line(&out, "tagged").contains("pushed 1 commit"), "{}", out);
+    assert_eq!(line(&out, "other"), "", "{}", out);
+    assert!(out.contains("Fetching 1 project..."), "{}", out);
+}
@@ -0,0 +1,110 @@
+//! Checks how `odin update` takes and validates commit messages.
+
+mod common;
+
+use std::fs;
+use common::{git, Fixture};
+
+fn with_commit_rules(fx: &Fixture, rules: &str) {
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n[commit]\n{}\n", config, rules)).unwrap();
+}
+
+fn last_message(fx: &Fixture) -> String {
+    git(&fx.work, &["log", "-1", "--format=%B"])
+}
+
+#[test]
+fn message_flag_skips_the_prompt() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("a.txt"), "a").unwrap();
+
+    let out = fx.update_with(&["--all", "-m", "Add a", "--no-push"], "");
+    assert!(out.contains("Committed with message: Add a"), "{}", out);
+    assert!(!out.contains("Enter commit message"), "{}", out);
+    assert_eq!(last_message(&fx), "Add a");
+}
+
+#[test]
+fn conventional_format_is_enforced() {
+    let fx = Fixture::new();
+    with_commit_rules(&fx, "format = \"conventional\"");
+    fs::write(fx.work.join("a.txt"), "a").unwrap();
+
+    let out = fx.update_with(&["--all", "-m", "Add a", "--no-push"], "");
+    assert!(out.contains("Subject must follow Conventional Commits"), "{}", out);
+    assert!(out.contains("your changes are still staged"), "{}", out);
+
+    let out = fx.update_with(&["--all", "-m", "feat(core): add a", "--no-push"], "");
+    assert!(out.contains("Committed with message: feat(core): add a"), "{}", out);
+}
+
+#[test]
+fn subject_length_and_issue_reference_are_checked() {
+    let fx = Fixture::new();
+    with_commit_rules(&fx, "max_subject_length = 20\nrequire_issue = true");
+    fs::write(fx.work.join("a.txt"), "a").unwrap();
+
+    let out = fx.update_with(&["--all", "-m", "A subject that is far too long"], "");
+    assert!(out.contains("Subject is 30 characters long; the limit is 20."), "{}", out);
+    assert!(out.contains("Message must reference an issue"), "{}", out);
+
+    let out = fx.update_with(&["--all", "-m", "Add a (#12)", "--no-push"], "");
+    assert!(out.contains("Committed with message: Add a (#12)"), "{}", out);
+}
+
+#[test]
+fn editor_starts_from_the_suggested_message() {
+    let fx = Fixture::new();
+    fs::create_dir_all(fx.work.join("src/commands")).unwrap();
+    fs::write(fx.work.join("src/commands/a.rs"), "a").unwrap();
+    fs::write(fx.work.join("src/commands/b.rs"), "b").unwrap();
+
+    let out = fx.update_with_env(&["--all", "--edit", "--no-push"], &[("EDITOR", "true"), ("VISUAL", "")], "");
+    assert!(out.contains("Committed with message: Add 2 files in src/commands"), "{}", out);
+}
+
+#[test]
+fn suggestion_uses_conventional_type_for_docs() {
+    let fx = Fixture::new();
+    with_commit_rules(&fx, "format = \"conventional\"");
+    git(&fx.work, &["commit", "-q", "-am", "chore: config"]);
+    fs::create_dir_all(fx.work.join("docs")).unwrap();
+    fs::write(fx.work.join("docs/guide.md"), "guide").unwrap();
+
+    let out = fx.update_with_env(&["--all", "--edit", "--no-push"], &[("EDITOR", "true"), ("VISUAL", "")], "");
+    assert!(out.contains("Committed with message: docs: add guide.md"), "{}", out);
+}
+
+#[test]
+fn yes_uses_the_suggestion_in_a_conventional_project() {
+    let fx = Fixture::new();
+    with_commit_rules(&fx, "format = \"conventional\"");
+    git(&fx.work, &["commit", "-q", "-am", "chore: config"]);
+    fs::create_dir_all(fx.work.join("docs")).unwrap();
+    fs::write(fx.work.join("docs/guide.md"), "guide").unwrap();
+
+    let out = fx.update_with(&["--all", "--yes", "--no-push"], "");
+    assert!(!out.contains("stdin is not a terminal"), "{}", out);
+    assert!(out.contains("Using suggested commit message: docs: add guide.md"), "{}", out);
+    assert_eq!(last_message(&fx), "docs: add guide.md");
+}
+
+#[test]
+fn fixes_adds_a_footer_that_satisfies_require_issue() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("CONFIG.toml"), "[app]\nname = \"work\"\n\n[commit]\nrequire_issue = true\n").unwrap();
+
+    let out = fx.update_with(&["--all", "-m", "Handle empty input", "--fixes", "12", "--fixes", "14", "--no-push"], "");
+    assert!(out.contains("Committed with message: Handle empty input"), "{}", out);
+    assert_eq!(git(&fx.work, &["log", "-1", "--format=%B"]), "Handle empty input\n\nFixes #12\nFixes #14");
+}
+
+#[test]
+fn fixes_is_not_mistaken_for_a_longer_issue_number() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("notes.txt"), "edit").unwrap();
+
+    fx.update_with(&["--all", "-m", "Handle empty input\n\nFixes #12", "--fixes", "1", "--fixes", "12", "--no-push"], "");
+    assert_eq!(git(&fx.work, &["log", "-1", "--format=%B"]), "Handle empty input\n\nFixes #12\nFixes #1");
+}
@@ -0,0 +1,85 @@
+//! Checks which files `odin update` stages.
+
+mod common;
+
+use std::fs;
+use common::{committed_files, git, Fixture};
+
+#[test]
+fn excluded_files_are_never_staged() {
+    let fx = Fixture::new();
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n[update]\nexclude = [\"*.log\", \"target/**\"]\n", config)).unwrap();
+    fs::create_dir_all(fx.work.join("target/debug")).unwrap();
+    fs::write(fx.work.join("target/debug/app"), "bin").unwrap();
+    fs::create_dir_all(fx.work.join("src")).unwrap();
+    fs::write(fx.work.join("src/debug.log"), "log").unwrap();
+    fs::write(fx.work.join("src/lib.rs"), "code").unwrap();
+
+    let out = fx.update_with(&["--all", "-m", "Add lib", "--no-push"], "");
+    assert!(out.contains("Skipping 2 excluded files"), "{}", out);
+    assert_eq!(committed_files(&fx.work), "CONFIG.toml\nsrc/lib.rs");
+    assert!(git(&fx.work, &["status", "--porcelain"]).contains("?? src/debug.log"));
+}
+
+#[test]
+fn already_staged_excluded_files_are_unstaged_when_nothing_else_changed() {
+    let fx = Fixture::new();
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n[update]\nexclude = [\"*.log\"]\n", config)).unwrap();
+    git(&fx.work, &["commit", "-q", "-am", "Exclude logs"]);
+    let head = git(&fx.work, &["rev-parse", "HEAD"]);
+    fs::write(fx.work.join("debug.log"), "log").unwrap();
+    git(&fx.work, &["add", "debug.log"]);
+
+    let out = fx.update_with(&["--yes", "-m", "Add log", "--no-push"], "");
+    assert!(out.contains("Skipping 1 excluded file"), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), head);
+    assert!(git(&fx.work, &["status", "--porcelain"]).contains("?? debug.log"));
+}
+
+#[test]
+fn path_arguments_limit_what_is_staged() {
+    let fx = Fixture::new();
+    fs::create_dir_all(fx.work.join("docs")).unwrap();
+    fs::write(fx.work.join("docs/guide.md"), "guide").unwrap();
+    fs::write(fx.work.join("notes.txt"), "notes").unwrap();
+
+    let out = fx.update_with(&["docs/", "-m", "Add guide", "--no-push"], "");
+    assert!(out.contains("Staged 1 file."), "{}", out);
+    assert_eq!(committed_files(&fx.work), "docs/guide.md");
+}
+
+#[test]
+fn files_staged_outside_the_path_arguments_are_left_out() {
+    let fx = Fixture::new();
+    fs::create_dir_all(fx.work.join("docs")).unwrap();
+    fs::write(fx.work.join("docs/guide.md"), "guide").unwrap();
+    fs::write(fx.work.join("secret.txt"), "secret").unwrap();
+    git(&fx.work, &["add", "secret.txt"]);
+
+    let out = fx.update_with(&["docs/", "-y", "-m", "Add guide", "--no-push"], "");
+    assert!(out.contains("Staged 1 file."), "{}", out);
+    assert_eq!(committed_files(&fx.work), "docs/guide.md");
+    assert!(git(&fx.work, &["status", "--porcelain"]).contains("?? secret.txt"));
+}
+
+#[test]
+fn picker_needs_a_terminal() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("b.txt"), "b").unwrap();
+    let out = fx.update("Add b\n");
+    assert!(out.contains("stdin is not a terminal. Pass --all, --yes or the paths to stage."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-list", "--count", "HEAD"]), "1");
+}
+
+#[test]
+fn yes_stages_tracked_changes_only() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("CONFIG.toml"), "[app]\nname = \"renamed\"\n").unwrap();
+    fs::write(fx.work.join("scratch.txt"), "scratch").unwrap();
+
+    let out = fx.update_with(&["--yes", "-m", "Rename", "--no-push"], "");
+    assert!(out.contains("Leaving 1 untracked file unstaged"), "{}", out);
+    assert_eq!(committed_files(&fx.work), "CONFIG.toml");
+}
@@ -0,0 +1,216 @@
+//! Drives `odin update` against a local bare remote to check the sync step.
+
+mod common;
+
+use std::fs;
+use common::{commit, git, Fixture};
+
+fn pushes(output: &str) -> usize {
+    output.matches("Pushing to remote...").count()
+}
+
+#[test]
+fn up_to_date_does_nothing() {
+    let fx = Fixture::new();
+    let out = fx.update("");
+    assert!(out.contains("up to date with origin/main"), "{}", out);
+    assert_eq!(pushes(&out), 0);
+}
+
+#[test]
+fn ahead_pushes_once() {
+    let fx = Fixture::new();
+    commit(&fx.work, "a.txt");
+    let out = fx.update_with(&["--yes"], "");
+    assert!(out.contains("1 commit ahead"), "{}", out);
+    assert_eq!(pushes(&out), 1);
+    assert_eq!(fx.remote_head("main"), git(&fx.work, &["rev-parse", "HEAD"]));
+}
+
+#[test]
+fn new_changes_are_committed_and_pushed_once() {
+    let fx = Fixture::new();
+    fs::write(fx.work.join("b.txt"), "b").unwrap();
+    let out = fx.update_with(&["--all", "-m", "Add b", "--yes"], "");
+    assert!(out.contains("Committed with message: Add b"), "{}", out);
+    assert_eq!(pushes(&out), 1);
+    assert_eq!(fx.remote_head("main"), git(&fx.work, &["rev-parse", "HEAD"]));
+}
+
+#[test]
+fn declining_push_leaves_remote_untouched() {
+    let fx = Fixture::new();
+    let before = fx.remote_head("main");
+    commit(&fx.work, "a.txt");
+    let out = fx.update_with(&["--no-push"], "");
+    assert_eq!(pushes(&out), 0);
+    assert_eq!(fx.remote_head("main"), before);
+}
+
+#[test]
+fn behind_offers_pull() {
+    let fx = Fixture::new();
+    let other = fx.other_clone();
+    commit(&other, "c.txt");
+    git(&other, &["push", "-q"]);
+
+    let out = fx.update_with(&["--pull"], "");
+    assert!(out.contains("1 commit behind"), "{}", out);
+    assert!(out.contains("Pulled from remote."), "{}", out);
+    assert_eq!(pushes(&out), 0);
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), fx.remote_head("main"));
+}
+
+#[test]
+fn diverged_does_not_push() {
+    let fx = Fixture::new();
+    let other = fx.other_clone();
+    commit(&other, "c.txt");
+    git(&other, &["push", "-q"]);
+    commit(&fx.work, "a.txt");
+
+    let out = fx.update_with(&["--yes"], "");
+    assert!(out.contains("diverged (1 ahead, 1 behind)"), "{}", out);
+    assert!(out.contains("Nothing was changed."), "{}", out);
+    assert_eq!(pushes(&out), 0);
+}
+
+#[test]
+fn branch_without_upstream_is_pushed_with_upstream() {
+    let fx = Fixture::new();
+    git(&fx.work, &["checkout", "-q", "-b", "feature"]);
+    commit(&fx.work, "f.txt");
+
+    let out = fx.update_with(&["--yes"], "");
+    assert!(out.contains("Branch 'feature' has no upstream branch."), "{}", out);
+    assert!(out.contains("Compared to origin/main: 1 commit ahead"), "{}", out);
+    assert_eq!(pushes(&out), 1);
+    assert_eq!(git(&fx.work, &["rev-parse", "--abbrev-ref", "@{u}"]), "origin/feature");
+}
+
+/// Leaves the working copy one commit ahead and one behind `origin/main`,
+/// both touching `shared.txt` if `conflict` is set.
+fn diverge(fx: &Fixture, conflict: bool) {
+    let other = fx.other_clone();
+    fs::write(other.join("shared.txt"), "theirs").unwrap();
+    git(&other, &["add", "."]);
+    git(&other, &["commit", "-q", "-m", "theirs"]);
+    git(&other, &["push", "-q"]);
+    let file = if conflict { "shared.txt" } else { "mine.txt" };
+    fs::write(fx.work.join(file), "mine").unwrap();
+    git(&fx.work, &["add", "."]);
+    git(&fx.work, &["commit", "-q", "-m", "mine"]);
+}
+
+/// Commits an `[update] divergence` setting to the working copy.
+fn set_divergence(fx: &Fixture, strategy: &str) {
+    let config = fs::read_to_string(fx.work.join("CONFIG.toml")).unwrap();
+    fs::write(fx.work.join("CONFIG.toml"), format!("{}\n[update]\ndivergence = \"{}\"\n", config, strategy)).unwrap();
+    git(&fx.work, &["commit", "-q", "-am", "config"]);
+}
+
+#[test]
+fn diverged_rebase_then_push() {
+    let fx = Fixture::new();
+    diverge(&fx, false);
+    set_divergence(&fx, "rebase");
+
+    let out = fx.update_with(&["--yes"], "");
+    assert!(out.contains("Rebase completed."), "{}", out);
+    assert_eq!(pushes(&out), 1);
+    assert_eq!(fx.remote_head("main"), git(&fx.work, &["rev-parse", "HEAD"]));
+    assert_eq!(git(&fx.work, &["rev-list", "--merges", "--count", "HEAD"]), "0");
+}
+
+#[test]
+fn diverged_default_comes_from_config() {
+    let fx = Fixture::new();
+    diverge(&fx, false);
+    set_divergence(&fx, "merge");
+
+    let out = fx.update_with(&["--pull", "--no-push"], "");
+    assert!(out.contains("(r)ebase, (M)erge or (a)bort? merge"), "{}", out);
+    assert!(out.contains("Merge completed."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-list", "--merges", "--count", "HEAD"]), "1");
+}
+
+#[test]
+fn conflicting_rebase_can_be_aborted_cleanly() {
+    let fx = Fixture::new();
+    diverge(&fx, true);
+    set_divergence(&fx, "rebase");
+    let before = git(&fx.work, &["rev-parse", "HEAD"]);
+
+    let out = fx.update_with(&["--pull"], "");
+    assert!(out.contains("conflicts in:\n  shared.txt"), "{}", out);
+    assert!(out.contains("Rebase aborted."), "{}", out);
+    assert_eq!(pushes(&out), 0);
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), before);
+    assert_eq!(git(&fx.work, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn without_a_terminal_missing_answers_are_errors() {
+    let fx = Fixture::new();
+    commit(&fx.work, "a.txt");
+    let before = fx.remote_head("main");
+
+    let out = fx.update("y\n");
+    assert!(out.contains("stdin is not a terminal. Pass --yes or --no-push."), "{}", out);
+    assert_eq!(fx.remote_head("main"), before);
+}
+
+#[test]
+fn no_adopt_leaves_unmanaged_directories_alone() {
+    let fx = Fixture::new();
+    git(&fx.work, &["rm", "-q", "CONFIG.toml"]);
+    git(&fx.work, &["commit", "-q", "-m", "Drop config"]);
+
+    let out = fx.update_with(&["--no-adopt"], "");
+    assert!(out.contains("Would you like to add it? no"), "{}", out);
+    assert!(out.contains("Aborting."), "{}", out);
+    assert!(!fx.work.join("CONFIG.toml").exists());
+}
+
+/// Pushes a change to line 1 of `notes.txt` from another clone and leaves an
+/// uncommitted change to line `line` in the working copy.
+fn behind_with_local_edit(fx: &Fixture, line: usize) {
+    let notes = "one\ntwo\nthree\nfour\nfive\n";
+    fs::write(fx.work.join("notes.txt"), notes).unwrap();
+    git(&fx.work, &["add", "notes.txt"]);
+    git(&fx.work, &["commit", "-q", "-m", "notes"]);
+    git(&fx.work, &["push", "-q"]);
+
+    let other = fx.other_clone();
+    fs::write(other.join("notes.txt"), notes.replacen("one", "ONE", 1)).unwrap();
+    git(&other, &["commit", "-q", "-am", "theirs"]);
+    git(&other, &["push", "-q"]);
+
+    let mut lines: Vec<&str> = notes.lines().collect();
+    lines[line - 1] = "mine";
+    fs::write(fx.work.join("notes.txt"), lines.join("\n") + "\n").unwrap();
+}
+
+#[test]
+fn pull_stashes_and_restores_uncommitted_changes() {
+    let fx = Fixture::new();
+    behind_with_local_edit(&fx, 5);
+
+    let out = fx.update_with(&["docs/", "--pull", "--no-push"], "");
+    assert!(out.contains("Stashed uncommitted changes."), "{}", out);
+    assert!(out.contains("Restored your uncommitted changes."), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), fx.remote_head("main"));
+    assert_eq!(fs::read_to_string(fx.work.join("notes.txt")).unwrap(), "ONE\ntwo\nthree\nfour\nmine\n");
+    assert_eq!(git(&fx.work, &["stash", "list"]), "");
+}
+
+#[test]
+fn conflicting_stash_pop_keeps_the_stash() {
+    let fx = Fixture::new();
+    behind_with_local_edit(&fx, 1);
+
+    let out = fx.update_with(&["docs/", "--pull", "--no-push"], "");
+    assert!(out.contains("Restoring your uncommitted changes conflicted in:\n  notes.txt"), "{}", out);
+    assert_eq!(git(&fx.work, &["rev-parse", "HEAD"]), fx.remote_head("main"));
+    assert_eq!(git(&fx.work, &["stash", "list", "--format=%s"]), "On main: odin update: changes set aside while pulling");
+}